@@ -1,4 +1,5 @@
 use colored::*;
+use crate::statistics::calculate_longest_streak;
 
 #[derive(Debug, Clone)]
 pub struct Achievement {
@@ -26,6 +27,15 @@ impl Tier {
             Tier::Legendary => |s: &str| s.bright_magenta(),
         }
     }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tier::Bronze => "Bronze",
+            Tier::Silver => "Silver",
+            Tier::Gold => "Gold",
+            Tier::Legendary => "Legendary",
+        }
+    }
     
 
 }
@@ -281,21 +291,6 @@ fn check_pattern_achievements(contributions: &[u32]) -> Vec<Achievement> {
     achievements
 }
 
-fn calculate_longest_streak(contributions: &[u32]) -> u32 {
-    let mut longest = 0;
-    let mut current = 0;
-    
-    for &count in contributions {
-        if count > 0 {
-            current += 1;
-            longest = longest.max(current);
-        } else {
-            current = 0;
-        }
-    }
-    longest
-}
-
 fn count_perfect_months(contributions: &[u32]) -> u32 {
     let mut perfect_months = 0;
     let mut current_streak = 0;
@@ -380,12 +375,12 @@ pub fn display_achievements(achievements: &[Achievement]) {
             println!("\n{}", format!("🏅 {} TIER", tier_name).bright_yellow().bold());
             for achievement in tier_achievements {
                 let tier_color = achievement.tier.color();
-                println!("{}", format!(
+                println!(
                     "   {} {} - {}",
                     achievement.icon,
                     tier_color(&achievement.name).bold(),
                     achievement.description.bright_white()
-                ));
+                );
             }
         }
     }
@@ -1,8 +1,11 @@
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use std::env;
-use crate::api::types::{GraphQLResponse, ContributionCalendar};
-use crate::api::queries::build_query_body;
+use crate::api::types::{GraphQLResponse, ContributionCalendar, Data, OrganizationData};
+use crate::api::queries::{build_query_body, build_year_query_body, build_org_members_query_body};
 
+/// Cheap to clone: clones share one connection pool.
+#[derive(Clone)]
 pub struct GitHubClient {
     client: Client,
     token: String,
@@ -31,8 +34,52 @@ impl GitHubClient {
     
 
     pub async fn fetch_contributions(&self, username: &str) -> Result<ContributionCalendar, Box<dyn std::error::Error>> {
-        let query_body = build_query_body(username);
+        self.fetch_calendar(build_query_body(username)).await
+    }
+
+    /// Contributions from January 1st to December 31st of `year`.
+    pub async fn fetch_year_contributions(&self, username: &str, year: i32) -> Result<ContributionCalendar, Box<dyn std::error::Error>> {
+        self.fetch_calendar(build_year_query_body(username, year)).await
+    }
+
+    /// Contributions for the last 12 months, or for `year` when given.
+    pub async fn fetch_contributions_in(&self, username: &str, year: Option<i32>) -> Result<ContributionCalendar, Box<dyn std::error::Error>> {
+        match year {
+            Some(year) => self.fetch_year_contributions(username, year).await,
+            None => self.fetch_contributions(username).await,
+        }
+    }
+
+    /// Logins of every member of `org`, following the pages 100 at a time.
+    pub async fn fetch_org_members(&self, org: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut logins = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let data: OrganizationData = self.post_query(build_org_members_query_body(org, cursor.as_deref())).await?;
+            let members = data
+                .organization
+                .ok_or_else(|| format!("Organization {} not found", org))?
+                .members_with_role;
+            logins.extend(members.nodes.into_iter().map(|member| member.login));
+            match members.page_info.end_cursor {
+                Some(end) if members.page_info.has_next_page => cursor = Some(end),
+                _ => return Ok(logins),
+            }
+        }
+    }
+
+    async fn fetch_calendar(&self, query_body: serde_json::Value) -> Result<ContributionCalendar, Box<dyn std::error::Error>> {
+        let data: Data = self.post_query(query_body).await?;
+        let calendar = data
+            .user
+            .ok_or("User not found")?
+            .contributions_collection
+            .contribution_calendar;
         
+        Ok(calendar)
+    }
+
+    async fn post_query<T: DeserializeOwned>(&self, query_body: serde_json::Value) -> Result<T, Box<dyn std::error::Error>> {
         let response = self.client
             .post("https://api.github.com/graphql")
             .header("Authorization", format!("Bearer {}", self.token))
@@ -46,7 +93,7 @@ impl GitHubClient {
             return Err(format!("GitHub API request failed with status: {}", response.status()).into());
         }
         
-        let graphql_response: GraphQLResponse = response.json().await?;
+        let graphql_response: GraphQLResponse<T> = response.json().await?;
         
 
         if let Some(errors) = graphql_response.errors {
@@ -54,15 +101,6 @@ impl GitHubClient {
             return Err(format!("GraphQL errors: {}", error_messages.join(", ")).into());
         }
         
-
-        let calendar = graphql_response
-            .data
-            .ok_or("No data in response")?
-            .user
-            .ok_or("User not found")?
-            .contributions_collection
-            .contribution_calendar;
-        
-        Ok(calendar)
+        Ok(graphql_response.data.ok_or("No data in response")?)
     }
-} 
\ No newline at end of file
+}
@@ -25,4 +25,61 @@ pub fn build_query_body(username: &str) -> serde_json::Value {
             "userName": username
         }
     })
-} 
\ No newline at end of file
+}
+
+/// Same as `CONTRIBUTION_QUERY` but for an explicit date range, used to fetch
+/// a whole calendar year.
+pub const RANGE_CONTRIBUTION_QUERY: &str = r#"
+query($userName: String!, $from: DateTime!, $to: DateTime!) {
+  user(login: $userName) {
+    contributionsCollection(from: $from, to: $to) {
+      contributionCalendar {
+        totalContributions
+        weeks {
+          contributionDays {
+            contributionCount
+            date
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+pub fn build_year_query_body(username: &str, year: i32) -> serde_json::Value {
+    serde_json::json!({
+        "query": RANGE_CONTRIBUTION_QUERY,
+        "variables": {
+            "userName": username,
+            "from": format!("{}-01-01T00:00:00Z", year),
+            "to": format!("{}-12-31T23:59:59Z", year)
+        }
+    })
+}
+
+pub const ORG_MEMBERS_QUERY: &str = r#"
+query($org: String!, $cursor: String) {
+  organization(login: $org) {
+    membersWithRole(first: 100, after: $cursor) {
+      pageInfo {
+        hasNextPage
+        endCursor
+      }
+      nodes {
+        login
+      }
+    }
+  }
+}
+"#;
+
+pub fn build_org_members_query_body(org: &str, cursor: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "query": ORG_MEMBERS_QUERY,
+        "variables": {
+            "org": org,
+            "cursor": cursor
+        }
+    })
+}
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
-pub struct GraphQLResponse {
-    pub data: Option<Data>,
+pub struct GraphQLResponse<T = Data> {
+    pub data: Option<T>,
     pub errors: Option<Vec<GraphQLError>>,
 }
 
@@ -16,6 +16,37 @@ pub struct Data {
     pub user: Option<User>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OrganizationData {
+    pub organization: Option<Organization>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Organization {
+    #[serde(rename = "membersWithRole")]
+    pub members_with_role: MemberConnection,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemberConnection {
+    #[serde(rename = "pageInfo")]
+    pub page_info: PageInfo,
+    pub nodes: Vec<Member>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PageInfo {
+    #[serde(rename = "hasNextPage")]
+    pub has_next_page: bool,
+    #[serde(rename = "endCursor")]
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Member {
+    pub login: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct User {
     #[serde(rename = "contributionsCollection")]
@@ -57,4 +88,12 @@ impl ContributionCalendar {
             .map(|day| day.contribution_count)
             .collect()
     }
+
+    pub fn flatten_dates(&self) -> Vec<String> {
+        self.weeks
+            .iter()
+            .flat_map(|week| &week.contribution_days)
+            .map(|day| day.date.clone())
+            .collect()
+    }
 } 
\ No newline at end of file
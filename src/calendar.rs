@@ -0,0 +1,78 @@
+/// Parses a `YYYY-MM-DD` date as returned by the GitHub API.
+pub fn parse_date(date: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.get(..2)?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+pub fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year as i64 - 1 } else { year as i64 };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Weekday of a date, with 0 = Sunday to match GitHub's calendar rows.
+pub fn weekday(date: &str) -> Option<usize> {
+    let (y, m, d) = parse_date(date)?;
+    Some((days_from_civil(y, m, d) + 4).rem_euclid(7) as usize)
+}
+
+pub fn year_of(date: &str) -> Option<i32> {
+    parse_date(date).map(|(y, _, _)| y)
+}
+
+pub fn month_of(date: &str) -> Option<u32> {
+    parse_date(date).map(|(_, m, _)| m)
+}
+
+pub fn month_abbrev(month: u32) -> &'static str {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    MONTHS[(month.clamp(1, 12) - 1) as usize]
+}
+
+/// Lays a flat daily series out as GitHub's week columns (Sunday first).
+/// Days before the first date or after the last one are `None`.
+pub fn weekly_grid<T: Clone>(values: &[T], dates: &[String]) -> Vec<[Option<T>; 7]> {
+    let offset = dates.first().and_then(|d| weekday(d)).unwrap_or(0);
+    let week_count = (offset + values.len()).div_ceil(7);
+    let mut grid: Vec<[Option<T>; 7]> = (0..week_count).map(|_| std::array::from_fn(|_| None)).collect();
+    for (i, value) in values.iter().enumerate() {
+        let slot = offset + i;
+        grid[slot / 7][slot % 7] = Some(value.clone());
+    }
+    grid
+}
+
+/// The date `days` after 1970-01-01, the inverse of `days_from_civil`.
+pub fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+    (year, month, day)
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`, the form GitHub dates days in.
+pub fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
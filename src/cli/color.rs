@@ -0,0 +1,30 @@
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// Decides once whether to emit ANSI colors and tells `colored` about it, so
+/// the renderer, achievements and interactive prompts all agree.
+///
+/// `always` and `never` win outright. In `auto` mode `CLICOLOR_FORCE` forces
+/// color on, then `NO_COLOR` (https://no-color.org) or `CLICOLOR=0` turn it
+/// off, and otherwise color is used only when stdout is a terminal.
+pub fn apply_color_policy(when: &str) -> Result<(), String> {
+    let enabled = match when.to_lowercase().as_str() {
+        "always" => true,
+        "never" => false,
+        "auto" => auto_color(),
+        other => return Err(format!("Unknown color mode '{}'. Use auto, always or never.", other)),
+    };
+    colored::control::set_override(enabled);
+    Ok(())
+}
+
+fn auto_color() -> bool {
+    let is_set = |name: &str| env::var(name).is_ok_and(|value| !value.is_empty());
+    if is_set("CLICOLOR_FORCE") && env::var("CLICOLOR_FORCE").as_deref() != Ok("0") {
+        return true;
+    }
+    if is_set("NO_COLOR") || env::var("CLICOLOR").as_deref() == Ok("0") {
+        return false;
+    }
+    io::stdout().is_terminal()
+}
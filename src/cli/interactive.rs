@@ -2,7 +2,9 @@ use colored::*;
 use std::io::{self, Write};
 use std::env;
 use crate::api::client::GitHubClient;
-use crate::renderer::skyline::render_skyline_with_options;
+use crate::renderer::building::Scale;
+use crate::renderer::skyline::{render_skyline_with_options, default_seed, RenderOptions};
+use crate::renderer::theme::{available_themes, themes_dir};
 
 pub fn show_splash_screen() {
     println!("{}", "╔══════════════════════════════════════════════════════════════════════════════╗".bright_cyan());
@@ -48,11 +50,14 @@ pub async fn interactive_mode() {
     }
     
     
-    let theme = get_theme_input();
-    let style = get_style_input();
-    let scale = get_scale_input();
-    let ascii_only = get_ascii_only_input();
-    let sky_mode = get_sky_mode_input();
+    let options = RenderOptions {
+        theme: get_theme_input(),
+        style: get_style_input(),
+        scale: get_scale_input(),
+        ascii_only: get_ascii_only_input(),
+        sky_mode: get_sky_mode_input(),
+        ..RenderOptions::default()
+    };
     
     
     let output_file = get_output_preference();
@@ -73,37 +78,22 @@ pub async fn interactive_mode() {
     match client.fetch_contributions(&username).await {
         Ok(calendar) => {
             let contributions = calendar.flatten_contributions();
+            let dates = calendar.flatten_dates();
             println!("{} Fetched {} days of contribution data", "✅".bright_green(), contributions.len());
             println!("{} Total contributions: {}", "📊".bright_blue(), calendar.total_contributions);
             println!();
+            let options = options.with_seed(default_seed(&username, &dates));
             
             if let Some(filename) = output_file {
                 
                 use crate::output::{render_skyline_to_string, save_skyline_to_file};
-                let (skyline_content, total_contribs) = render_skyline_to_string(
-                    &contributions,
-                    &theme,
-                    &username,
-                    &style,
-                    &scale,
-                    ascii_only,
-                    &sky_mode,
-                    None,
-                );
-                match save_skyline_to_file(&skyline_content, &filename, &username, &theme, total_contribs) {
+                let (skyline_content, total_contribs) = render_skyline_to_string(&contributions, &dates, &username, &options);
+                match save_skyline_to_file(&skyline_content, &filename, &username, &options.theme, total_contribs, "text") {
                     Ok(_) => {},
                     Err(e) => println!("{} Error saving to file: {}", "❌".bright_red(), e),
                 }
             } else {
-                render_skyline_with_options(
-                    &contributions,
-                    &theme,
-                    &style,
-                    &scale,
-                    ascii_only,
-                    &sky_mode,
-                    None,
-                );
+                render_skyline_with_options(&contributions, &dates, &options);
             }
         }
         Err(e) => {
@@ -119,27 +109,38 @@ fn get_style_input() -> String {
     println!("{}", "   [2] Blocks".bright_blue());
     println!("{}", "   [3] ASCII".bright_green());
     println!("{}", "   [4] Hash (#)".bright_cyan());
+    println!("{}", "   [5] Isometric 3D".bright_yellow());
+    println!("{}", "   [6] Calendar heatmap".bright_white());
     print!("{}", "   > ".bright_yellow());
     io::stdout().flush().unwrap();
     let mut choice = String::new();
     if io::stdin().read_line(&mut choice).is_ok() {
-        match choice.trim() { "2" => "blocks".into(), "3" => "ascii".into(), "4" => "hash".into(), _ => "braille".into() }
+        match choice.trim() { "2" => "blocks".into(), "3" => "ascii".into(), "4" => "hash".into(), "5" => "iso".into(), "6" => "heatmap".into(), _ => "braille".into() }
     } else { "braille".into() }
 }
 
-fn get_scale_input() -> String {
+fn get_scale_input() -> Scale {
     println!("{}", "📐 Scaling".bright_cyan().bold());
     println!("{}", "   Choose scaling algorithm:".bright_white());
     println!("{}", "   [1] Dramatic (default)".bright_magenta());
     println!("{}", "   [2] Linear".bright_blue());
     println!("{}", "   [3] Sqrt".bright_green());
     println!("{}", "   [4] Log".bright_cyan());
+    println!("{}", "   [5] Percentile 95 - clip outlier days".bright_yellow());
+    println!("{}", "   [6] Rank - evenly spaced by rank".bright_white());
     print!("{}", "   > ".bright_yellow());
     io::stdout().flush().unwrap();
     let mut choice = String::new();
     if io::stdin().read_line(&mut choice).is_ok() {
-        match choice.trim() { "2" => "linear".into(), "3" => "sqrt".into(), "4" => "log".into(), _ => "dramatic".into() }
-    } else { "dramatic".into() }
+        match choice.trim() {
+            "2" => Scale::Linear,
+            "3" => Scale::Sqrt,
+            "4" => Scale::Log,
+            "5" => Scale::Percentile(95.0),
+            "6" => Scale::Rank,
+            _ => Scale::Dramatic,
+        }
+    } else { Scale::Dramatic }
 }
 
 fn get_ascii_only_input() -> bool {
@@ -154,12 +155,24 @@ fn get_ascii_only_input() -> bool {
 fn get_sky_mode_input() -> String {
     println!("{}", "🌌 Sky".bright_cyan().bold());
     println!("{}", "   Sky detail:".bright_white());
-    println!("{}", "   [1] Detailed (default)".bright_magenta());
+    println!("{}", "   [1] Night (default)".bright_magenta());
     println!("{}", "   [2] None".bright_blue());
+    println!("{}", "   [3] Day - sun and clouds, lights off".bright_yellow());
+    println!("{}", "   [4] Sunset - bands in your theme's colors".bright_red());
+    println!("{}", "   [5] Weather - rain or snow when you've been quiet".bright_cyan());
     print!("{}", "   > ".bright_yellow());
     io::stdout().flush().unwrap();
     let mut choice = String::new();
-    if io::stdin().read_line(&mut choice).is_ok() { if choice.trim() == "2" { "none".into() } else { "detailed".into() } } else { "detailed".into() }
+    if io::stdin().read_line(&mut choice).is_err() {
+        return "detailed".into();
+    }
+    match choice.trim() {
+        "2" => "none".into(),
+        "3" => "day".into(),
+        "4" => "sunset".into(),
+        "5" => "weather".into(),
+        _ => "detailed".into(),
+    }
 }
 
 fn get_username_input() -> String {
@@ -179,41 +192,51 @@ fn get_username_input() -> String {
 }
 
 fn get_theme_input() -> String {
+    let themes = available_themes();
+
     println!();
     println!("{}", "🎨 Theme Selection".bright_cyan().bold());
     println!("{}", "   Choose your preferred color theme:".bright_white());
-    println!("{}", "   [1] 🌆 Synthwave (default) - Neon cyan and magenta vibes".bright_cyan());
-    println!("{}", "   [2] 🧛 Dracula - Dark theme with vibrant colors".bright_magenta());
-    println!("{}", "   [3] ☀️  Solarized - Warm, balanced earth tones".bright_yellow());
-    println!("{}", "   [4] 🌃 Cyberpunk - Electric magenta and cyan future".bright_magenta());
-    println!("{}", "   [5] 🟢 Matrix - Digital green rain aesthetic".bright_green());
-    println!("{}", "   [6] 🌅 Sunset - Warm reds, oranges, and yellows".bright_red());
-    println!("{}", "   [7] 🎲 Random - Surprise me!".bright_white());
+    for (i, theme) in themes.iter().enumerate() {
+        let default_marker = if theme.name == "synthwave" { " (default)" } else { "" };
+        let line = format!("   [{}] {} {}{} - {}", i + 1, theme.emoji, capitalize(&theme.name), default_marker, theme.description);
+        println!("{}", theme.antenna_color.paint(&line));
+    }
+    println!("{}", format!("   [{}] 🎲 Random - Surprise me!", themes.len() + 1).bright_white());
+    if let Some(dir) = themes_dir() {
+        println!("{}", format!("   💡 Add your own .toml/.json themes to {}", dir.display()).bright_black());
+    }
     print!("{}", "   > ".bright_yellow());
     io::stdout().flush().unwrap();
-    
+
     let mut choice = String::new();
-    match io::stdin().read_line(&mut choice) {
-        Ok(_) => {
-            match choice.trim() {
-                "1" | "" => "synthwave".to_string(),
-                "2" => "dracula".to_string(),
-                "3" => "solarized".to_string(),
-                "4" => "cyberpunk".to_string(),
-                "5" => "matrix".to_string(),
-                "6" => "sunset".to_string(),
-                "7" => "random".to_string(),
-                _ => {
-                    println!("{}", "   🎨 Using default theme (synthwave)".bright_cyan());
-                    "synthwave".to_string()
-                }
+    if io::stdin().read_line(&mut choice).is_ok() {
+        let choice = choice.trim();
+        if choice.is_empty() {
+            return "synthwave".to_string();
+        }
+        if let Ok(number) = choice.parse::<usize>() {
+            if let Some(theme) = number.checked_sub(1).and_then(|i| themes.get(i)) {
+                return theme.name.clone();
+            }
+            if number == themes.len() + 1 {
+                return "random".to_string();
             }
         }
-        Err(_) => {
-            println!("{}", "   🎨 Using default theme (synthwave)".bright_cyan());
-            "synthwave".to_string()
+        if let Some(theme) = themes.iter().find(|t| t.name.eq_ignore_ascii_case(choice)) {
+            return theme.name.clone();
         }
     }
+    println!("{}", "   🎨 Using default theme (synthwave)".bright_cyan());
+    "synthwave".to_string()
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
 }
 
 fn get_output_preference() -> Option<String> {
@@ -1 +1,2 @@
-pub mod interactive; 
\ No newline at end of file
+pub mod color;
+pub mod interactive;
@@ -0,0 +1,199 @@
+use colored::*;
+use crate::achievements::{calculate_achievements, Achievement};
+use crate::output::strip_ansi_codes;
+use crate::renderer::building::{CellSize, ScaleReference};
+use crate::renderer::skyline::{building_lines, RenderOptions};
+use crate::statistics::Statistics;
+
+/// One side of a comparison: a user, or a user in a given year.
+pub struct Series {
+    pub label: String,
+    pub contributions: Vec<u32>,
+    pub dates: Vec<String>,
+}
+
+impl Series {
+    /// The most recent `days`, so a rolling calendar keeps today.
+    fn last_days(&self, days: usize) -> Series {
+        Series {
+            label: self.label.clone(),
+            contributions: self.contributions[self.contributions.len() - days..].to_vec(),
+            dates: self.dates[self.dates.len().saturating_sub(days)..].to_vec(),
+        }
+    }
+}
+
+/// Prints two skylines on one scale, aligned on their last day, with a row
+/// marking which side was busier, their statistics side by side and the
+/// achievements only one of them unlocked.
+pub fn render_comparison(a: &Series, b: &Series, options: &RenderOptions) {
+    let days = a.contributions.len().min(b.contributions.len());
+    if days == 0 {
+        println!("{}", "❌ No contribution data to compare!".bright_red().bold());
+        return;
+    }
+    let trimmed: Vec<String> = [a, b]
+        .iter()
+        .filter(|series| series.contributions.len() > days)
+        .map(|series| match series.contributions.len() - days {
+            1 => format!("{} leaves out its first day", series.label),
+            extra => format!("{} leaves out its first {} days", series.label, extra),
+        })
+        .collect();
+    let (a, b) = (a.last_days(days), b.last_days(days));
+
+    let mut options = options.clone();
+    if options.reference.is_none() {
+        options.reference = Some(ScaleReference {
+            label: format!("{} and {}", a.label, b.label),
+            contributions: [a.contributions.as_slice(), b.contributions.as_slice()].concat(),
+        });
+    }
+    let width = options.shown_days(days);
+
+    println!("\n{}", "╔═══════════════════════════════════════════════════════════════╗".bright_cyan().bold());
+    println!("{}", "║                    ⚖️  SKYLINE COMPARISON ⚖️                   ║".bright_cyan().bold());
+    println!("{}", "╚═══════════════════════════════════════════════════════════════╝".bright_cyan().bold());
+    if let Some(reference) = &options.reference {
+        println!("{}", format!("📏 Shared scale: {} (busiest day {})", reference.label, reference.max()).bright_yellow().bold());
+    }
+    println!("{}", format!("⚖️  Scale: {}    🎭 Style: {}", options.scale, options.style).bright_cyan().bold());
+    if !trimmed.is_empty() {
+        println!("{}", format!("📆 Aligned on the last {} days: {}", days, trimmed.join(", ")).bright_yellow());
+    }
+
+    // Pad the shorter city from the top so both grounds line up.
+    let mut skylines = [
+        building_lines(&a.contributions, &a.dates, &options, width),
+        building_lines(&b.contributions, &b.dates, &options, width),
+    ];
+    let rows = skylines.iter().map(Vec::len).max().unwrap_or(0);
+    let blank = " ".repeat(options.cell.columns(width));
+    for lines in &mut skylines {
+        lines.splice(0..0, vec![blank.clone(); rows - lines.len()]);
+    }
+    // Then drop the empty sky both share, keeping one row of air.
+    let is_blank = |line: &String| strip_ansi_codes(line).trim().is_empty();
+    let empty = skylines.iter().map(|lines| lines.iter().take_while(|line| is_blank(line)).count()).min().unwrap_or(0);
+    for (series, lines) in [&a, &b].into_iter().zip(&mut skylines) {
+        lines.drain(..empty.saturating_sub(1));
+        let shown = &series.dates[series.dates.len().saturating_sub(width)..];
+        let range = match (shown.first(), shown.last()) {
+            (Some(first), Some(last)) => format!("  {} → {}", first, last),
+            _ => String::new(),
+        };
+        println!("\n{}{}", format!("🏙️  {}", series.label).bright_magenta().bold(), range.bright_black());
+        for line in lines.iter() {
+            println!("{}", line);
+        }
+    }
+
+    let offset = days - width;
+    println!("{}", difference_row(&a.contributions[offset..], &b.contributions[offset..], options.cell, options.ascii_only));
+    let (up, down) = if options.ascii_only { ("^", "v") } else { ("▲", "▼") };
+    println!(
+        "{}",
+        format!("{} {} busier   {} {} busier", up, a.label, down, b.label).bright_black()
+    );
+    let busier_a = a.contributions.iter().zip(&b.contributions).filter(|(x, y)| x > y).count();
+    let busier_b = a.contributions.iter().zip(&b.contributions).filter(|(x, y)| x < y).count();
+    println!(
+        "{}",
+        format!(
+            "📆 Over {} days: {} was busier on {}, {} on {}, tied on {}",
+            days, a.label, busier_a, b.label, busier_b, days - busier_a - busier_b
+        )
+        .bright_cyan()
+    );
+
+    print_statistics_table(&a, &b);
+    print_achievements_diff(&a, &b);
+}
+
+/// A marker under each shown day: up when `a` was busier, down when `b` was.
+fn difference_row(a: &[u32], b: &[u32], cell: CellSize, ascii: bool) -> String {
+    let (up, down, tie) = if ascii { ("^", "v", ".") } else { ("▲", "▼", "·") };
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| match x.cmp(y) {
+            std::cmp::Ordering::Greater => cell.center(up).bright_green().bold().to_string(),
+            std::cmp::Ordering::Less => cell.center(down).bright_red().bold().to_string(),
+            std::cmp::Ordering::Equal => cell.center(tie).bright_black().to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(&cell.spacer())
+}
+
+fn print_statistics_table(a: &Series, b: &Series) {
+    let (sa, sb) = (Statistics::new(&a.contributions, &a.dates), Statistics::new(&b.contributions, &b.dates));
+    let column = a.label.chars().count().max(b.label.chars().count()).clamp(8, 20);
+    let fit = |label: &str| label.chars().take(column).collect::<String>();
+    // (name, a, b, decimals)
+    let rows = [
+        ("Total contributions", sa.total as f32, sb.total as f32, 0),
+        ("Average per day", sa.average, sb.average, 1),
+        ("Max daily", sa.max_day as f32, sb.max_day as f32, 0),
+        ("Active days", sa.active_days as f32, sb.active_days as f32, 0),
+        ("Active share %", sa.active_percent(), sb.active_percent(), 1),
+        ("Longest streak", sa.longest_streak.days as f32, sb.longest_streak.days as f32, 0),
+        ("Current streak", sa.current_streak.days as f32, sb.current_streak.days as f32, 0),
+        ("Longest gap", sa.longest_gap.days as f32, sb.longest_gap.days as f32, 0),
+    ];
+
+    println!("\n{}", "📊 STATISTICS".bright_blue().bold());
+    println!(
+        "{}",
+        format!("   {:<20} {:>column$} {:>column$} {:>8}", "", fit(&a.label), fit(&b.label), "Δ").bright_blue().bold()
+    );
+    for (name, x, y, decimals) in rows {
+        let delta = x - y;
+        let delta_text = format!("{:>+8.*}", decimals, delta);
+        let delta_text = if delta > 0.0 {
+            delta_text.bright_green().bold()
+        } else if delta < 0.0 {
+            delta_text.bright_red().bold()
+        } else {
+            delta_text.bright_black()
+        };
+        println!(
+            "   {} {} {} {}",
+            format!("{:<20}", name).bright_white(),
+            format!("{:>column$.*}", decimals, x).bright_yellow(),
+            format!("{:>column$.*}", decimals, y).bright_yellow(),
+            delta_text
+        );
+    }
+}
+
+fn print_achievements_diff(a: &Series, b: &Series) {
+    let (earned_a, earned_b) = (calculate_achievements(&a.contributions), calculate_achievements(&b.contributions));
+    let has = |list: &[Achievement], achievement: &Achievement| list.iter().any(|other| other.name == achievement.name);
+    let both: Vec<&Achievement> = earned_a.iter().filter(|x| has(&earned_b, x)).collect();
+    let only_a: Vec<&Achievement> = earned_a.iter().filter(|x| !has(&earned_b, x)).collect();
+    let only_b: Vec<&Achievement> = earned_b.iter().filter(|x| !has(&earned_a, x)).collect();
+
+    println!("\n{}", "🏆 ACHIEVEMENTS".bright_cyan().bold());
+    if earned_a.is_empty() && earned_b.is_empty() {
+        println!("   {}", "No achievements unlocked on either side yet.".bright_black());
+        return;
+    }
+    for (title, list) in [
+        (format!("Only {}", a.label), only_a),
+        (format!("Only {}", b.label), only_b),
+        ("Both".to_string(), both),
+    ] {
+        if list.is_empty() {
+            continue;
+        }
+        println!("{}", format!("🏅 {} ({})", title, list.len()).bright_yellow().bold());
+        for achievement in list {
+            let tier_color = achievement.tier.color();
+            println!(
+                "   {} {} - {}",
+                achievement.icon,
+                tier_color(&achievement.name).bold(),
+                achievement.description.bright_white()
+            );
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::fs;
+use colored::*;
+use serde::Serialize;
+use crate::achievements::{calculate_achievements, Tier};
+use crate::output::strip_ansi_codes;
+use crate::statistics::Statistics;
+use crate::team::Team;
+
+/// Columns `--sort` can rank the leaderboard by.
+pub const SORT_KEYS: [&str; 5] = ["total", "active", "streak", "current", "achievements"];
+
+const TIERS: [Tier; 4] = [Tier::Legendary, Tier::Gold, Tier::Silver, Tier::Bronze];
+
+/// One member's row: their statistics and achievements per tier.
+pub struct Entry {
+    pub login: String,
+    pub stats: Statistics,
+    /// Achievements earned in each of `TIERS`, best first.
+    pub tiers: [usize; 4],
+}
+
+impl Entry {
+    fn new(login: &str, contributions: &[u32], dates: &[String]) -> Self {
+        let achievements = calculate_achievements(contributions);
+        Entry {
+            login: login.to_string(),
+            stats: Statistics::new(contributions, dates),
+            tiers: TIERS.map(|tier| achievements.iter().filter(|a| a.tier == tier).count()),
+        }
+    }
+
+    pub fn achievements(&self) -> usize {
+        self.tiers.iter().sum()
+    }
+
+    fn sort_value(&self, key: &str) -> u32 {
+        match key {
+            "active" => self.stats.active_days as u32,
+            "streak" => self.stats.longest_streak.days,
+            "current" => self.stats.current_streak.days,
+            // Better tiers outrank any number of lower ones.
+            "achievements" => self.tiers.iter().fold(0, |score, &count| score * 100 + count as u32),
+            _ => self.stats.total,
+        }
+    }
+}
+
+/// Every member's entry, best first by `sort`; ties go to the bigger total,
+/// then alphabetically.
+pub fn rank_members(team: &Team, sort: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = team
+        .members
+        .iter()
+        .map(|(login, contributions)| Entry::new(login, contributions, &team.dates))
+        .collect();
+    entries.sort_by(|a, b| {
+        b.sort_value(sort)
+            .cmp(&a.sort_value(sort))
+            .then(b.stats.total.cmp(&a.stats.total))
+            .then(a.login.to_lowercase().cmp(&b.login.to_lowercase()))
+    });
+    entries
+}
+
+/// The ranked table for the terminal, podium places in tier colors.
+pub fn leaderboard_lines(team_name: &str, entries: &[Entry], sort: &str) -> Vec<String> {
+    let mut lines = vec![
+        String::new(),
+        "╔═══════════════════════════════════════════════════════════════╗".bright_cyan().bold().to_string(),
+        "║                      🏆 LEADERBOARD 🏆                        ║".bright_cyan().bold().to_string(),
+        "╚═══════════════════════════════════════════════════════════════╝".bright_cyan().bold().to_string(),
+        format!("🏢 {} · {} members · sorted by {}", team_name, entries.len(), sort).bright_yellow().bold().to_string(),
+        String::new(),
+        format!(
+            "{:>4}  {:<20} {:>7} {:>7} {:>8} {:>8} {:>5} {:>5} {:>5} {:>5}",
+            "#", "User", "Total", "Active", "Longest", "Current", "Leg", "Gold", "Silv", "Brnz"
+        )
+        .bright_blue()
+        .bold()
+        .to_string(),
+    ];
+    for (index, entry) in entries.iter().enumerate() {
+        let rank = format!("{:>4}", index + 1);
+        let rank = match index {
+            0 => Tier::Gold.color()(&rank).bold(),
+            1 => Tier::Silver.color()(&rank).bold(),
+            2 => Tier::Bronze.color()(&rank).bold(),
+            _ => rank.bright_black(),
+        };
+        // A streak that ends unless they contribute today is flagged.
+        let current = format!("{:>7}{}", entry.stats.current_streak.days, if entry.stats.streak_at_risk { "!" } else { " " });
+        let current = if entry.stats.streak_at_risk { current.bright_yellow().bold() } else { current.bright_magenta() };
+        let tiers: Vec<String> = entry
+            .tiers
+            .iter()
+            .zip(&TIERS)
+            .map(|(count, tier)| tier.color()(&format!("{:>5}", count)).to_string())
+            .collect();
+        lines.push(format!(
+            "{}  {} {} {} {} {} {}",
+            rank,
+            format!("{:<20}", entry.login).bright_white().bold(),
+            format!("{:>7}", entry.stats.total).bright_yellow(),
+            format!("{:>7}", entry.stats.active_days).bright_green(),
+            format!("{:>8}", entry.stats.longest_streak.days).bright_magenta(),
+            current,
+            tiers.join(" ")
+        ));
+    }
+    lines
+}
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    rank: usize,
+    login: &'a str,
+    total: u32,
+    active_days: usize,
+    longest_streak: u32,
+    current_streak: u32,
+    current_streak_start: Option<&'a str>,
+    streak_at_risk: bool,
+    longest_gap: u32,
+    achievements: JsonTiers,
+}
+
+#[derive(Serialize)]
+struct JsonTiers {
+    legendary: usize,
+    gold: usize,
+    silver: usize,
+    bronze: usize,
+    total: usize,
+}
+
+pub fn leaderboard_json(entries: &[Entry]) -> String {
+    let rows: Vec<JsonRow> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| JsonRow {
+            rank: index + 1,
+            login: &entry.login,
+            total: entry.stats.total,
+            active_days: entry.stats.active_days,
+            longest_streak: entry.stats.longest_streak.days,
+            current_streak: entry.stats.current_streak.days,
+            current_streak_start: entry.stats.current_streak.start.as_deref(),
+            streak_at_risk: entry.stats.streak_at_risk,
+            longest_gap: entry.stats.longest_gap.days,
+            achievements: JsonTiers {
+                legendary: entry.tiers[0],
+                gold: entry.tiers[1],
+                silver: entry.tiers[2],
+                bronze: entry.tiers[3],
+                total: entry.achievements(),
+            },
+        })
+        .collect();
+    serde_json::to_string_pretty(&rows).unwrap_or_default()
+}
+
+pub fn leaderboard_csv(entries: &[Entry]) -> String {
+    let mut csv = String::from("rank,login,total,active_days,longest_streak,current_streak,streak_at_risk,longest_gap,legendary,gold,silver,bronze,achievements\n");
+    for (index, entry) in entries.iter().enumerate() {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            index + 1,
+            entry.login,
+            entry.stats.total,
+            entry.stats.active_days,
+            entry.stats.longest_streak.days,
+            entry.stats.current_streak.days,
+            entry.stats.streak_at_risk,
+            entry.stats.longest_gap.days,
+            entry.tiers[0],
+            entry.tiers[1],
+            entry.tiers[2],
+            entry.tiers[3],
+            entry.achievements()
+        ));
+    }
+    csv
+}
+
+/// Prints the leaderboard in `format` (text, json or csv), or saves it to
+/// `output` with the colors left out.
+pub fn write_leaderboard(team: &Team, sort: &str, format: &str, output: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = rank_members(team, sort);
+    let content = match format {
+        "json" => leaderboard_json(&entries),
+        "csv" => leaderboard_csv(&entries),
+        _ => leaderboard_lines(&team.name, &entries, sort).join("\n"),
+    };
+    match output {
+        Some(path) => {
+            let plain = strip_ansi_codes(&content);
+            fs::write(path, plain.trim_start())?;
+            eprintln!("{}", format!("✅ Leaderboard saved to: {}", path).bright_green().bold());
+        }
+        None => println!("{}", content),
+    }
+    Ok(())
+}
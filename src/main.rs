@@ -4,10 +4,28 @@ mod renderer;
 mod cli;
 mod output;
 mod achievements;
+mod calendar;
+mod mesh;
+mod statistics;
+mod compare;
+mod team;
+mod leaderboard;
 use api::client::GitHubClient;
-use renderer::skyline::render_skyline_with_options;
+use renderer::skyline::{render_skyline_with_options, default_seed, RenderOptions, COLUMN_STYLES};
+use renderer::animation::animate_skyline;
+use renderer::timelapse::save_timelapse_gif;
+use renderer::theme::{load_theme, load_theme_file};
+use renderer::coloring::COLOR_MODES;
+use renderer::sky_elements::{MOON_MODES, SKY_MODES};
+use renderer::ground::GROUND_MODES;
+use renderer::building::{CellSize, Scale, ScaleReference};
+use cli::color::apply_color_policy;
 use cli::interactive::{show_splash_screen, interactive_mode};
-use output::{render_skyline_to_string, save_skyline_to_file};
+use mesh::{layout_heights, build_city_mesh, model_label, save_mesh_to_file, MESH_LAYOUTS};
+use compare::{render_comparison, Series};
+use leaderboard::{write_leaderboard, SORT_KEYS};
+use team::{fetch_team, read_users_file, render_team_skyline, DEFAULT_JOBS};
+use output::{render_statistics_json, render_statistics_csv, save_export_to_file, render_skyline_to_string, save_skyline_to_file, render_markdown_card, inject_into_readme};
 
 
 #[derive(Parser)]
@@ -20,10 +38,16 @@ struct Args {
     username: Option<String>,
     
    
-    #[arg(long, help = "Color theme for the skyline (synthwave, dracula, solarized, cyberpunk, matrix, sunset, random)")]
+    #[arg(long, help = "Color theme for the skyline (synthwave, dracula, solarized, cyberpunk, matrix, sunset, random, or a theme from your themes directory)")]
     theme: Option<String>,
+
+    #[arg(long, conflicts_with = "theme", help = "Load the color theme from a .toml or .json theme file")]
+    theme_file: Option<String>,
     
    
+    #[arg(long, value_name = "WHEN", help = "When to use colors (auto, always, never) [default: auto]")]
+    color: Option<String>,
+
     #[arg(long, help = "Skip interactive mode")]
     no_interactive: bool,
     
@@ -31,28 +55,109 @@ struct Args {
     #[arg(short, long, help = "Output file to save the skyline (e.g., skyline.txt)")]
     output: Option<String>,
 
-    #[arg(long, help = "Rendering style (braille, blocks, ascii)")]
+    #[arg(long, help = "Rendering style (braille, blocks, ascii, hash, iso, heatmap)")]
     style: Option<String>,
 
-    #[arg(long, help = "Scaling algorithm (linear, sqrt, log, dramatic)")]
+    #[arg(long, help = "Scaling algorithm (linear, sqrt, log, dramatic, rank, pow:<k>, percentile:<p>)")]
     scale: Option<String>,
 
     #[arg(long, help = "Force ASCII-only characters (no Unicode)")]
     ascii_only: bool,
 
-    #[arg(long, help = "Sky mode (none, detailed)")]
+    #[arg(long, help = "Sky mode (detailed = night, day, sunset, weather, none)")]
     sky: Option<String>,
 
+    #[arg(long, help = "Moon phase from total contributions or the real phase on the last shown day (contributions, astronomical)")]
+    moon: Option<String>,
+
+    #[arg(long, help = "Foreground under the buildings (water, reflection, street, park, bridge, none) [default: the theme's]")]
+    ground: Option<String>,
+
+    #[arg(long, help = "Building colors by (height, gradient, weekday, month, level)")]
+    color_by: Option<String>,
+
     #[arg(long, help = "Max days to display (width), e.g., 25")]
     width: Option<usize>,
+
+    #[arg(long, value_name = "N", help = "Scale heights against a busiest day of N contributions instead of your own")]
+    max: Option<u32>,
+
+    #[arg(long, value_name = "USER|FILE", conflicts_with = "max", help = "Scale heights against another user's contributions, or counts read from a file")]
+    scale_ref: Option<String>,
+
+    #[arg(long, help = "Rows the tallest building reaches, 5-100 [default: 30]")]
+    height: Option<u32>,
+
+    #[arg(long, help = "Columns per building, 1-5 [default: 3]")]
+    cell_width: Option<usize>,
+
+    #[arg(long, help = "Blank columns between buildings, 0-4 [default: 1]")]
+    gap: Option<usize>,
+
+    #[arg(long, help = "Animate the city being built before showing it (terminal only)")]
+    animate: bool,
+
+    #[arg(long, help = "Frames per second for --animate (default 30) and gif output (default 12)")]
+    fps: Option<u32>,
+
+    #[arg(long, help = "Output format (text, markdown, stl, obj, gif, or json and csv for statistics)")]
+    format: Option<String>,
+
+    #[arg(long, help = "Days added per gif frame (day, week)")]
+    frame_step: Option<String>,
+
+    #[arg(long, help = "3D model layout for stl/obj (grid = weeks x weekdays, row = one row of days)")]
+    mesh_layout: Option<String>,
+
+    #[arg(long, help = "Seed for stars and --theme random, for reproducible output [default: hash of username and date range]")]
+    seed: Option<u64>,
+
+    #[arg(long, value_name = "YYYY", help = "Show a calendar year instead of the last 12 months")]
+    year: Option<i32>,
+
+    #[arg(long, value_name = "USER", help = "Compare against another user on a shared scale")]
+    compare: Option<String>,
+
+    #[arg(long, value_name = "YYYY", conflicts_with = "compare", help = "Compare against your own contributions in YYYY on a shared scale")]
+    compare_year: Option<i32>,
+
+    #[arg(long, value_delimiter = ',', value_name = "USERS", help = "Team mode: combine these users' contributions into one city (comma separated)")]
+    users: Vec<String>,
+
+    #[arg(long, value_name = "FILE", help = "Team mode: read usernames from a file, one per line")]
+    users_file: Option<String>,
+
+    #[arg(long, help = "Team mode: combine every member of a GitHub organization")]
+    org: Option<String>,
+
+    #[arg(long, help = "Stack each team member's share of a day in their own color (terminal only)")]
+    stack: bool,
+
+    #[arg(long, conflicts_with_all = ["stack", "compare", "compare_year", "animate"], help = "Rank the team's members in a table instead of drawing a city")]
+    leaderboard: bool,
+
+    #[arg(long, help = "Leaderboard order (total, active, streak, current, achievements) [default: total]")]
+    sort: Option<String>,
+
+    #[arg(long, help = "Team members fetched at the same time, 1-16 [default: 4]")]
+    jobs: Option<usize>,
+
+    #[arg(long, help = "README file to update between <!-- skyline:start --> and <!-- skyline:end --> markers (markdown format)")]
+    inject: Option<String>,
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
+
+    if let Err(e) = apply_color_policy(args.color.as_deref().unwrap_or("auto")) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
     
    
-    if args.username.is_none() && !args.no_interactive {
+    let team_mode = !args.users.is_empty() || args.users_file.is_some() || args.org.is_some();
+    if args.username.is_none() && !team_mode && !args.no_interactive {
         show_splash_screen();
        
         loop {
@@ -61,20 +166,174 @@ async fn main() {
     }
     
    
-    let username = match args.username {
-        Some(u) => u,
-        None => {
-            eprintln!("❌ Username required in CLI mode. Use --help for more info.");
-            std::process::exit(1);
+    // In team mode the positional username, if any, is one more member.
+    let username = if team_mode {
+        args.org.clone().unwrap_or_else(|| "team".to_string())
+    } else {
+        match args.username.clone() {
+            Some(u) => u,
+            None => {
+                eprintln!("❌ Username required in CLI mode. Use --help for more info.");
+                std::process::exit(1);
+            }
         }
     };
     
-    let theme = args.theme.unwrap_or_else(|| "synthwave".to_string());
-    let style = args.style.unwrap_or_else(|| "braille".to_string());
-    let scale = args.scale.unwrap_or_else(|| "dramatic".to_string());
-    let sky_mode = args.sky.unwrap_or_else(|| "detailed".to_string());
+    let theme_name = match &args.theme_file {
+        Some(path) => load_theme_file(path).map(|theme| theme.name),
+        None => {
+            let name = args.theme.unwrap_or_else(|| "synthwave".to_string());
+            load_theme(&name).map(|_| name)
+        }
+    };
+    let theme_name = theme_name.unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+
+    let options = RenderOptions {
+        theme: theme_name,
+        style: args.style.unwrap_or_else(|| "braille".to_string()),
+        scale: match args.scale.as_deref().map(Scale::parse) {
+            Some(Ok(scale)) => scale,
+            Some(Err(e)) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+            None => Scale::Dramatic,
+        },
+        ascii_only: args.ascii_only,
+        sky_mode: args.sky.unwrap_or_else(|| "detailed".to_string()).to_lowercase(),
+        width: args.width,
+        color_by: args.color_by.unwrap_or_else(|| "height".to_string()).to_lowercase(),
+        moon: args.moon.unwrap_or_else(|| "contributions".to_string()).to_lowercase(),
+        ground: args.ground.map(|ground| ground.to_lowercase()),
+        height: args.height.unwrap_or(30),
+        cell: CellSize {
+            width: args.cell_width.unwrap_or(3),
+            gap: args.gap.unwrap_or(1),
+        },
+        ..RenderOptions::default()
+    };
+    let format = args.format.unwrap_or_else(|| {
+        if args.inject.is_some() { "markdown".to_string() } else { "text".to_string() }
+    }).to_lowercase();
+
+    if args.leaderboard {
+        if !["text", "json", "csv"].contains(&format.as_str()) {
+            eprintln!("❌ --leaderboard prints text, json or csv, not '{}'.", format);
+            std::process::exit(1);
+        }
+    } else if !["text", "markdown", "stl", "obj", "gif", "json", "csv"].contains(&format.as_str()) {
+        eprintln!("❌ Unknown output format '{}'. Use text, markdown, stl, obj, gif, json or csv.", format);
+        std::process::exit(1);
+    }
+    let mesh_layout = args.mesh_layout.as_deref().unwrap_or("grid").to_lowercase();
+    if !MESH_LAYOUTS.contains(&mesh_layout.as_str()) {
+        eprintln!("❌ Unknown mesh layout '{}'. Use {}.", mesh_layout, MESH_LAYOUTS.join(", "));
+        std::process::exit(1);
+    }
+    let sort = args.sort.as_deref().unwrap_or("total").to_lowercase();
+    if !SORT_KEYS.contains(&sort.as_str()) {
+        eprintln!("❌ Unknown sort '{}'. Use {}.", sort, SORT_KEYS.join(", "));
+        std::process::exit(1);
+    }
+    if args.leaderboard && !team_mode {
+        eprintln!("❌ --leaderboard needs a team: --users, --users-file or --org.");
+        std::process::exit(1);
+    }
+    if !COLOR_MODES.contains(&options.color_by.as_str()) {
+        eprintln!("❌ Unknown color mode '{}'. Use {}.", options.color_by, COLOR_MODES.join(", "));
+        std::process::exit(1);
+    }
+    if !SKY_MODES.contains(&options.sky_mode.as_str()) {
+        eprintln!("❌ Unknown sky mode '{}'. Use {}.", options.sky_mode, SKY_MODES.join(", "));
+        std::process::exit(1);
+    }
+    if !MOON_MODES.contains(&options.moon.as_str()) {
+        eprintln!("❌ Unknown moon mode '{}'. Use {}.", options.moon, MOON_MODES.join(", "));
+        std::process::exit(1);
+    }
+    if let Some(ground) = &options.ground
+        && !GROUND_MODES.contains(&ground.as_str())
+    {
+        eprintln!("❌ Unknown ground '{}'. Use {}.", ground, GROUND_MODES.join(", "));
+        std::process::exit(1);
+    }
+    if !(5..=100).contains(&options.height) {
+        eprintln!("❌ --height must be between 5 and 100 rows.");
+        std::process::exit(1);
+    }
+    if !(1..=5).contains(&options.cell.width) {
+        eprintln!("❌ --cell-width must be between 1 and 5 columns.");
+        std::process::exit(1);
+    }
+    if options.cell.gap > 4 {
+        eprintln!("❌ --gap must be between 0 and 4 columns.");
+        std::process::exit(1);
+    }
+    if [args.year, args.compare_year].iter().flatten().any(|&year| year < FIRST_YEAR) {
+        eprintln!("❌ Years before {} have no GitHub contributions.", FIRST_YEAR);
+        std::process::exit(1);
+    }
+    let comparing = args.compare.is_some() || args.compare_year.is_some();
+    if comparing && !COLUMN_STYLES.contains(&options.style.to_lowercase().as_str()) {
+        eprintln!("❌ --compare and --compare-year draw {} styles only.", COLUMN_STYLES.join(", "));
+        std::process::exit(1);
+    }
+    if comparing && (format != "text" || args.output.is_some() || args.animate) {
+        eprintln!("❌ --compare and --compare-year print to the terminal and cannot be combined with --format, --output or --animate.");
+        std::process::exit(1);
+    }
+    if team_mode && comparing {
+        eprintln!("❌ --compare and --compare-year work with a single user, not a team.");
+        std::process::exit(1);
+    }
+    if args.stack && (!team_mode || format != "text" || args.output.is_some()) {
+        eprintln!("❌ --stack needs a team (--users, --users-file or --org) and prints to the terminal only.");
+        std::process::exit(1);
+    }
+    if args.animate && !COLUMN_STYLES.contains(&options.style.to_lowercase().as_str()) {
+        eprintln!("❌ --animate draws {} styles only.", COLUMN_STYLES.join(", "));
+        std::process::exit(1);
+    }
+    if args.animate && (format != "text" || args.output.is_some()) {
+        eprintln!("❌ --animate plays in the terminal and cannot be combined with --format or --output.");
+        std::process::exit(1);
+    }
+    if args.stack && !COLUMN_STYLES.contains(&options.style.to_lowercase().as_str()) {
+        eprintln!("❌ --stack draws {} styles only.", COLUMN_STYLES.join(", "));
+        std::process::exit(1);
+    }
+    let jobs = args.jobs.unwrap_or(DEFAULT_JOBS);
+    if !(1..=16).contains(&jobs) {
+        eprintln!("❌ --jobs must be between 1 and 16.");
+        std::process::exit(1);
+    }
+    if options.width == Some(0) {
+        eprintln!("❌ --width must be at least 1 day.");
+        std::process::exit(1);
+    }
+    if args.max == Some(0) {
+        eprintln!("❌ --max must be at least 1.");
+        std::process::exit(1);
+    }
+    if ["stl", "obj", "gif"].contains(&format.as_str()) && args.output.is_none() {
+        eprintln!("❌ --format {} requires an output file (-o city.{}).", format, format);
+        std::process::exit(1);
+    }
+    if args.inject.is_some() && format != "markdown" {
+        eprintln!("❌ --inject requires --format markdown.");
+        std::process::exit(1);
+    }
     
-    println!("🚀 Generating skyline for GitHub user: {}", username);
+    if args.leaderboard {
+        eprintln!("🚀 Building leaderboard for: {}", username);
+    } else if team_mode {
+        eprintln!("🚀 Generating team skyline for: {}", username);
+    } else {
+        eprintln!("🚀 Generating skyline for GitHub user: {}", username);
+    }
     
     let client = match GitHubClient::new() {
         Ok(client) => client,
@@ -85,38 +344,178 @@ async fn main() {
         }
     };
     
-    match client.fetch_contributions(&username).await {
-        Ok(calendar) => {
-            let contributions = calendar.flatten_contributions();
-            println!("✅ Fetched {} days of contribution data", contributions.len());
-            println!("📊 Total contributions: {}", calendar.total_contributions);
+    let reference = match (args.max, &args.scale_ref) {
+        (Some(max), _) => Some(ScaleReference::fixed(max)),
+        (None, Some(path)) if std::path::Path::new(path).is_file() => match ScaleReference::from_file(path) {
+            Ok(reference) => Some(reference),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        },
+        (None, Some(user)) => match client.fetch_contributions(user).await {
+            Ok(calendar) => Some(ScaleReference { label: user.clone(), contributions: calendar.flatten_contributions() }),
+            Err(e) => {
+                eprintln!("❌ Error fetching contributions for scale reference {}: {}", user, e);
+                std::process::exit(1);
+            }
+        },
+        (None, None) => None,
+    };
+    if let Some(reference) = &reference
+        && reference.max() == 0
+    {
+        eprintln!("❌ Scale reference {} has no contributions to scale against.", reference.label);
+        std::process::exit(1);
+    }
+    let options = RenderOptions { reference, ..options };
+
+    let team = if team_mode {
+        let mut logins: Vec<String> = args.username.iter().chain(&args.users).cloned().collect();
+        if let Some(path) = &args.users_file {
+            match read_users_file(path) {
+                Ok(users) => logins.extend(users),
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        if let Some(org) = &args.org {
+            match client.fetch_org_members(org).await {
+                Ok(members) => logins.extend(members),
+                Err(e) => {
+                    eprintln!("❌ Error fetching members of {}: {}", org, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        logins.retain(|login| seen.insert(login.to_lowercase()));
+        if logins.is_empty() {
+            eprintln!("❌ The team has no members.");
+            std::process::exit(1);
+        }
+        eprintln!("👥 Fetching {} team members, {} at a time", logins.len(), jobs);
+        match fetch_team(&client, &username, logins, args.year, jobs).await {
+            Ok(team) => Some(team),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    if args.leaderboard
+        && let Some(team) = &team
+    {
+        if let Err(e) = write_leaderboard(team, &sort, &format, args.output.as_deref()) {
+            eprintln!("❌ Error saving leaderboard: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    let calendar = match &team {
+        Some(team) => Ok((team.totals(), team.dates.clone())),
+        None => client.fetch_contributions_in(&username, args.year)
+            .await
+            .map(|calendar| (calendar.flatten_contributions(), calendar.flatten_dates())),
+    };
+
+    match calendar {
+        Ok((contributions, dates)) => {
+            eprintln!("✅ Fetched {} days of contribution data", contributions.len());
+            eprintln!("📊 Total contributions: {}", contributions.iter().sum::<u32>());
+            let options = options.with_seed(args.seed.unwrap_or_else(|| default_seed(&username, &dates)));
             
-            if let Some(output_file) = args.output {
+            if comparing {
+                let label = |year: Option<i32>| match year {
+                    Some(year) => format!("{} {}", username, year),
+                    None => username.clone(),
+                };
+                let (other_label, other_calendar) = match (&args.compare, args.compare_year) {
+                    (Some(other), _) => (other.clone(), client.fetch_contributions_in(other, args.year).await),
+                    (None, year) => (label(year), client.fetch_contributions_in(&username, year).await),
+                };
+                let other_calendar = other_calendar.unwrap_or_else(|e| {
+                    eprintln!("❌ Error fetching contributions for {}: {}", other_label, e);
+                    std::process::exit(1);
+                });
+                let mine = Series {
+                    label: if args.compare_year.is_some() { label(args.year) } else { username.clone() },
+                    contributions,
+                    dates,
+                };
+                let theirs = Series {
+                    label: other_label,
+                    contributions: other_calendar.flatten_contributions(),
+                    dates: other_calendar.flatten_dates(),
+                };
+                render_comparison(&mine, &theirs, &options);
+            } else if format == "gif" {
+                let output_file = args.output.as_deref().unwrap_or_default();
+                let step = args.frame_step.as_deref().unwrap_or("week");
+                match save_timelapse_gif(&contributions, &dates, &options, output_file, step, args.fps.unwrap_or(12)) {
+                    Ok(_) => println!("🎉 Time-lapse generation complete!"),
+                    Err(e) => eprintln!("❌ Error saving time-lapse: {}", e),
+                }
+            } else if format == "stl" || format == "obj" {
+                let heights = layout_heights(&contributions, &dates, &mesh_layout, &options);
+                let label = model_label(&username, &dates);
+                let mesh = build_city_mesh(&heights, &label);
+                let output_file = args.output.as_deref().unwrap_or_default();
+                match save_mesh_to_file(&mesh, output_file, &format, &label) {
+                    Ok(_) => println!("🎉 3D skyline generation complete!"),
+                    Err(e) => eprintln!("❌ Error saving model: {}", e),
+                }
+            } else if format == "json" || format == "csv" {
+                let export = if format == "json" {
+                    render_statistics_json(&contributions, &dates, &username)
+                } else {
+                    render_statistics_csv(&contributions, &dates)
+                };
+                match &args.output {
+                    Some(output_file) => {
+                        if let Err(e) = save_export_to_file(&export, output_file) {
+                            eprintln!("❌ Error saving to file: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                    None => println!("{}", export),
+                }
+            } else if format == "markdown" {
+                let markdown = render_markdown_card(&contributions, &dates, &username, &options);
+                if let Some(output_file) = &args.output
+                    && let Err(e) = save_skyline_to_file(&markdown, output_file, &username, &options.theme, contributions.iter().sum(), &format)
+                {
+                    eprintln!("❌ Error saving to file: {}", e);
+                    std::process::exit(1);
+                }
+                if let Some(readme) = &args.inject
+                    && let Err(e) = inject_into_readme(&markdown, readme)
+                {
+                    eprintln!("❌ Error updating README: {}", e);
+                    std::process::exit(1);
+                }
+                if args.output.is_none() && args.inject.is_none() {
+                    println!("{}", markdown);
+                }
+            } else if let Some(output_file) = args.output {
                
-                let (skyline_content, total_contribs) = render_skyline_to_string(
-                    &contributions,
-                    &theme,
-                    &username,
-                    &style,
-                    &scale,
-                    args.ascii_only,
-                    &sky_mode,
-                    args.width,
-                );
-                match save_skyline_to_file(&skyline_content, &output_file, &username, &theme, total_contribs) {
+                let (skyline_content, total_contribs) = render_skyline_to_string(&contributions, &dates, &username, &options);
+                match save_skyline_to_file(&skyline_content, &output_file, &username, &options.theme, total_contribs, &format) {
                     Ok(_) => println!("🎉 Skyline generation complete!"),
                     Err(e) => eprintln!("❌ Error saving to file: {}", e),
                 }
             } else {
-                render_skyline_with_options(
-                    &contributions,
-                    &theme,
-                    &style,
-                    &scale,
-                    args.ascii_only,
-                    &sky_mode,
-                    args.width,
-                );
+                if args.animate {
+                    animate_skyline(&contributions, &dates, &options, args.fps.unwrap_or(30));
+                }
+                match &team {
+                    Some(team) if args.stack => render_team_skyline(team, &options),
+                    _ => render_skyline_with_options(&contributions, &dates, &options),
+                }
             }
         }
         Err(e) => {
@@ -126,3 +525,5 @@ async fn main() {
     }
 }
 
+/// The first year with GitHub contributions.
+const FIRST_YEAR: i32 = 2008;
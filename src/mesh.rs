@@ -0,0 +1,448 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use colored::*;
+use crate::calendar::{weekly_grid, year_of};
+use crate::renderer::font::glyph_rows;
+use crate::renderer::skyline::RenderOptions;
+
+/// Accepted `--mesh-layout` values.
+pub const MESH_LAYOUTS: [&str; 2] = ["grid", "row"];
+
+// All measurements are in millimetres with Z pointing up, which is what
+// slicers expect for STL files. Each floor of `--height` is a millimetre.
+const CELL_SIZE: f32 = 2.5;
+const BUILDING_FOOTPRINT: f32 = 2.1;
+const PLATE_MARGIN: f32 = 4.0;
+const PLATE_THICKNESS: f32 = 3.0;
+const LABEL_STRIP_DEPTH: f32 = 9.0;
+const LABEL_PIXEL: f32 = 1.0;
+const LABEL_DEPTH: f32 = 0.8;
+/// Margin around each engraved pixel, so pixels that only touch at a corner
+/// stay apart instead of pinching the surface.
+const LABEL_GAP: f32 = 0.1;
+
+pub struct Mesh {
+    vertices: Vec<[f32; 3]>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// A rectangle of the model seen from above, and the height of its top.
+struct Block {
+    min: [f32; 2],
+    max: [f32; 2],
+    top: f32,
+}
+
+impl Block {
+    fn contains(&self, x: f32, y: f32) -> bool {
+        (self.min[0]..self.max[0]).contains(&x) && (self.min[1]..self.max[1]).contains(&y)
+    }
+}
+
+impl Mesh {
+    fn new() -> Self {
+        Mesh { vertices: Vec::new(), triangles: Vec::new() }
+    }
+
+    /// The closed surface of a `width` by `depth` slab standing on `z = 0`
+    /// whose top is at `base`, except inside `blocks`, which set their own
+    /// top. The slab is cut into a grid along every block edge and each grid
+    /// point gets one vertex per height, so neighbouring faces share edges
+    /// and the result is a single watertight solid.
+    fn heightfield(width: f32, depth: f32, base: f32, blocks: &[Block]) -> Self {
+        let xs = grid_lines(width, blocks.iter().flat_map(|b| [b.min[0], b.max[0]]));
+        let ys = grid_lines(depth, blocks.iter().flat_map(|b| [b.min[1], b.max[1]]));
+        let (nx, ny) = (xs.len() - 1, ys.len() - 1);
+        let tops: Vec<Vec<f32>> = (0..nx)
+            .map(|i| {
+                (0..ny)
+                    .map(|j| {
+                        let (x, y) = ((xs[i] + xs[i + 1]) / 2.0, (ys[j] + ys[j + 1]) / 2.0);
+                        blocks.iter().find(|b| b.contains(x, y)).map_or(base, |b| b.top)
+                    })
+                    .collect()
+            })
+            .collect();
+        // Outside the slab counts as height 0, so its sides come out as
+        // ordinary walls down to the bottom.
+        let top = |i: usize, j: usize| -> f32 {
+            if (1..=nx).contains(&i) && (1..=ny).contains(&j) { tops[i - 1][j - 1] } else { 0.0 }
+        };
+        // Heights meeting at grid point (i, j), ascending.
+        let levels = |i: usize, j: usize| -> Vec<f32> {
+            let mut levels = vec![top(i, j), top(i + 1, j), top(i, j + 1), top(i + 1, j + 1)];
+            levels.sort_by(f32::total_cmp);
+            levels.dedup();
+            levels
+        };
+
+        let mut grid = GridMesh { mesh: Mesh::new(), xs: &xs, ys: &ys, index: HashMap::new() };
+        for (i, column) in tops.iter().enumerate() {
+            for (j, &z) in column.iter().enumerate() {
+                let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)].map(|(x, y)| grid.vertex(x, y, z));
+                grid.quad(corners, [0.0, 0.0, 1.0]);
+            }
+        }
+        // Walls wherever neighbouring cells differ, facing the lower one.
+        // Grid cell (i, j) is `top(i + 1, j + 1)`.
+        for i in 0..=nx {
+            for j in 0..ny {
+                let (left, right) = (top(i, j + 1), top(i + 1, j + 1));
+                if left != right {
+                    let facing = if left > right { [1.0, 0.0, 0.0] } else { [-1.0, 0.0, 0.0] };
+                    grid.wall((i, j), (i, j + 1), left.min(right), left.max(right), &levels, facing);
+                }
+            }
+        }
+        for j in 0..=ny {
+            for i in 0..nx {
+                let (front, back) = (top(i + 1, j), top(i + 1, j + 1));
+                if front != back {
+                    let facing = if front > back { [0.0, 1.0, 0.0] } else { [0.0, -1.0, 0.0] };
+                    grid.wall((i, j), (i + 1, j), front.min(back), front.max(back), &levels, facing);
+                }
+            }
+        }
+
+        // The bottom is a fan around its centre through every grid point on
+        // the outline, which the side walls end on.
+        let outline: Vec<(usize, usize)> = (0..nx)
+            .map(|i| (i, 0))
+            .chain((0..ny).map(|j| (nx, j)))
+            .chain((1..=nx).rev().map(|i| (i, ny)))
+            .chain((1..=ny).rev().map(|j| (0, j)))
+            .collect();
+        let centre = grid.mesh.vertices.len();
+        grid.mesh.vertices.push([width / 2.0, depth / 2.0, 0.0]);
+        for (k, &(i, j)) in outline.iter().enumerate() {
+            let (ni, nj) = outline[(k + 1) % outline.len()];
+            let (a, b) = (grid.vertex(i, j, 0.0), grid.vertex(ni, nj, 0.0));
+            grid.mesh.triangle([centre, a, b], [0.0, 0.0, -1.0]);
+        }
+
+        grid.mesh
+    }
+
+    /// Adds a triangle, wound so its normal points along `facing`.
+    fn triangle(&mut self, [a, b, c]: [usize; 3], facing: [f32; 3]) {
+        let n = normal(self.vertices[a], self.vertices[b], self.vertices[c]);
+        if n[0] * facing[0] + n[1] * facing[1] + n[2] * facing[2] >= 0.0 {
+            self.triangles.push([a, b, c]);
+        } else {
+            self.triangles.push([a, c, b]);
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn to_binary_stl(&self, name: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(84 + self.triangles.len() * 50);
+        let mut header = [b' '; 80];
+        let title = format!("GitHub Skyline - {}", name);
+        for (slot, byte) in header.iter_mut().zip(title.bytes()) {
+            *slot = byte;
+        }
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&(self.triangles.len() as u32).to_le_bytes());
+
+        for triangle in &self.triangles {
+            let [a, b, c] = triangle.map(|i| self.vertices[i]);
+            for value in normal(a, b, c).iter().chain(&a).chain(&b).chain(&c) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&0u16.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn to_obj(&self, name: &str) -> String {
+        let mut output = String::new();
+        output.push_str("# GitHub Skyline 3D model (units: mm, Z up)\n");
+        output.push_str(&format!("o {}\n", name.replace(char::is_whitespace, "_")));
+        for [x, y, z] in &self.vertices {
+            output.push_str(&format!("v {:.3} {:.3} {:.3}\n", x, y, z));
+        }
+        for [a, b, c] in &self.triangles {
+            output.push_str(&format!("f {} {} {}\n", a + 1, b + 1, c + 1));
+        }
+        output
+    }
+}
+
+/// Builds a mesh on the grid of a heightfield, with one shared vertex per
+/// grid point and height.
+struct GridMesh<'a> {
+    mesh: Mesh,
+    xs: &'a [f32],
+    ys: &'a [f32],
+    index: HashMap<(usize, usize, u32), usize>,
+}
+
+impl GridMesh<'_> {
+    fn vertex(&mut self, i: usize, j: usize, z: f32) -> usize {
+        let vertices = &mut self.mesh.vertices;
+        let position = [self.xs[i], self.ys[j], z];
+        *self.index.entry((i, j, z.to_bits())).or_insert_with(|| {
+            vertices.push(position);
+            vertices.len() - 1
+        })
+    }
+
+    fn quad(&mut self, [a, b, c, d]: [usize; 4], facing: [f32; 3]) {
+        self.mesh.triangle([a, b, c], facing);
+        self.mesh.triangle([a, c, d], facing);
+    }
+
+    /// Fills the vertical strip between grid points `p` and `q` from `low` to
+    /// `high`. Each side stops at every height that meets at its grid point,
+    /// so the strip shares its edges with the faces around it.
+    fn wall(
+        &mut self,
+        p: (usize, usize),
+        q: (usize, usize),
+        low: f32,
+        high: f32,
+        levels: &impl Fn(usize, usize) -> Vec<f32>,
+        facing: [f32; 3],
+    ) {
+        let side = |(i, j): (usize, usize)| -> Vec<f32> {
+            let mut side = levels(i, j);
+            side.retain(|&z| (low..=high).contains(&z));
+            side
+        };
+        let (ps, qs) = (side(p), side(q));
+        let (mut a, mut b) = (0, 0);
+        while a + 1 < ps.len() || b + 1 < qs.len() {
+            let pa = self.vertex(p.0, p.1, ps[a]);
+            let qb = self.vertex(q.0, q.1, qs[b]);
+            if b + 1 == qs.len() || (a + 1 < ps.len() && ps[a + 1] <= qs[b + 1]) {
+                let next = self.vertex(p.0, p.1, ps[a + 1]);
+                self.mesh.triangle([pa, next, qb], facing);
+                a += 1;
+            } else {
+                let next = self.vertex(q.0, q.1, qs[b + 1]);
+                self.mesh.triangle([pa, next, qb], facing);
+                b += 1;
+            }
+        }
+    }
+}
+
+/// Sorted, distinct cut positions across `0..=size`, including both ends.
+fn grid_lines(size: f32, cuts: impl Iterator<Item = f32>) -> Vec<f32> {
+    let mut lines: Vec<f32> = [0.0, size].into_iter().chain(cuts.filter(|&c| c > 0.0 && c < size)).collect();
+    lines.sort_by(f32::total_cmp);
+    lines.dedup();
+    lines
+}
+
+fn normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if len > 0.0 { [n[0] / len, n[1] / len, n[2] / len] } else { [0.0, 0.0, 0.0] }
+}
+
+/// Building heights for the model, indexed as `[row][column]`. The grid
+/// layout mirrors GitHub's calendar (7 weekday rows by week columns); the row
+/// layout is a single line of the most recent days, like the terminal skyline.
+pub fn layout_heights(
+    contributions: &[u32],
+    dates: &[String],
+    layout: &str,
+    options: &RenderOptions,
+) -> Vec<Vec<u32>> {
+    let scale = options.height_scale(contributions, options.height);
+    let height = |count: u32| scale.height(count);
+
+    match layout.to_lowercase().as_str() {
+        "row" => {
+            let width = options.shown_days(contributions.len());
+            let recent = &contributions[contributions.len() - width..];
+            vec![recent.iter().map(|&c| height(c)).collect()]
+        }
+        _ => {
+            let weeks = weekly_grid(contributions, dates);
+            (0..7)
+                .map(|day| weeks.iter().map(|week| week[day].map_or(0, height)).collect())
+                .collect()
+        }
+    }
+}
+
+/// Raises each day as a tower from a base plate, with the label engraved
+/// along the front edge of the plate, all as one closed solid.
+pub fn build_city_mesh(heights: &[Vec<u32>], label: &str) -> Mesh {
+    let rows = heights.len();
+    let columns = heights.iter().map(|r| r.len()).max().unwrap_or(0);
+
+    let label = label.to_uppercase();
+    let label_px = label_width_px(&label) as f32;
+    let city_width = columns as f32 * CELL_SIZE;
+    let plate_width = city_width.max(label_px * LABEL_PIXEL) + 2.0 * PLATE_MARGIN;
+    let plate_depth = rows as f32 * CELL_SIZE + LABEL_STRIP_DEPTH + 2.0 * PLATE_MARGIN;
+
+    let mut blocks = Vec::new();
+    let city_x = (plate_width - city_width) / 2.0;
+    let city_y = PLATE_MARGIN + LABEL_STRIP_DEPTH;
+    let inset = (CELL_SIZE - BUILDING_FOOTPRINT) / 2.0;
+    for (r, row) in heights.iter().enumerate() {
+        // Row 0 sits at the back so the model reads like the on-screen grid.
+        let y = city_y + (rows - 1 - r) as f32 * CELL_SIZE + inset;
+        for (c, &h) in row.iter().enumerate() {
+            if h == 0 {
+                continue;
+            }
+            let x = city_x + c as f32 * CELL_SIZE + inset;
+            blocks.push(Block {
+                min: [x, y],
+                max: [x + BUILDING_FOOTPRINT, y + BUILDING_FOOTPRINT],
+                top: PLATE_THICKNESS + h as f32,
+            });
+        }
+    }
+
+    let text_x = (plate_width - label_px * LABEL_PIXEL) / 2.0;
+    let text_y = PLATE_MARGIN + (LABEL_STRIP_DEPTH - 5.0 * LABEL_PIXEL) / 2.0;
+    blocks.extend(label_blocks(&label, text_x, text_y));
+
+    Mesh::heightfield(plate_width, plate_depth, PLATE_THICKNESS, &blocks)
+}
+
+pub fn model_label(username: &str, dates: &[String]) -> String {
+    match dates.last().and_then(|d| year_of(d)) {
+        Some(year) => format!("{} {}", username, year),
+        None => username.to_string(),
+    }
+}
+
+pub fn save_mesh_to_file(
+    mesh: &Mesh,
+    filename: &str,
+    format: &str,
+    label: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        "stl" => fs::write(filename, mesh.to_binary_stl(label))?,
+        "obj" => fs::write(filename, mesh.to_obj(label))?,
+        other => return Err(format!("Unsupported model format '{}'", other).into()),
+    }
+
+    println!("{}", format!("✅ 3D model saved to: {}", filename).bright_green().bold());
+    println!("{}", format!("🧊 Triangles: {}    📁 File size: {} bytes", mesh.triangle_count(), fs::metadata(filename)?.len()).bright_blue());
+
+    Ok(())
+}
+
+fn label_width_px(label: &str) -> usize {
+    (label.chars().count() * 4).saturating_sub(1)
+}
+
+/// Grooves spelling `label`. Each pixel is cut slightly smaller than its
+/// cell and joined to its right and upper neighbours, so strokes read as one
+/// groove while diagonal pixels never share a corner.
+fn label_blocks(label: &str, x: f32, y: f32) -> Vec<Block> {
+    let mut pixels = BTreeSet::new();
+    for (i, ch) in label.chars().enumerate() {
+        for (row, bits) in glyph_rows(ch).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    // Glyph rows are stored top-down; the plate's front edge is y = 0.
+                    pixels.insert((i * 4 + col, 4 - row));
+                }
+            }
+        }
+    }
+
+    let edge = |origin: f32, px: usize| origin + px as f32 * LABEL_PIXEL;
+    let groove = |x0: f32, x1: f32, y0: f32, y1: f32| Block { min: [x0, y0], max: [x1, y1], top: PLATE_THICKNESS - LABEL_DEPTH };
+    let mut blocks = Vec::new();
+    for &(px, py) in &pixels {
+        let (left, right) = (edge(x, px), edge(x, px + 1));
+        let (bottom, top) = (edge(y, py), edge(y, py + 1));
+        blocks.push(groove(left + LABEL_GAP, right - LABEL_GAP, bottom + LABEL_GAP, top - LABEL_GAP));
+        if pixels.contains(&(px + 1, py)) {
+            blocks.push(groove(right - LABEL_GAP, right + LABEL_GAP, bottom + LABEL_GAP, top - LABEL_GAP));
+        }
+        if pixels.contains(&(px, py + 1)) {
+            blocks.push(groove(left + LABEL_GAP, right - LABEL_GAP, top - LABEL_GAP, top + LABEL_GAP));
+        }
+    }
+    // Where three or four pixels meet, fill the corner between them too.
+    let corners: BTreeSet<(usize, usize)> = pixels
+        .iter()
+        .flat_map(|&(px, py)| [(px, py), (px + 1, py), (px, py + 1), (px + 1, py + 1)])
+        .collect();
+    for (cx, cy) in corners {
+        let meeting = [(0, 0), (1, 0), (0, 1), (1, 1)]
+            .iter()
+            .filter(|&&(dx, dy)| cx.checked_sub(dx).zip(cy.checked_sub(dy)).is_some_and(|pixel| pixels.contains(&pixel)))
+            .count();
+        if meeting >= 3 {
+            let (cx, cy) = (edge(x, cx), edge(y, cy));
+            blocks.push(groove(cx - LABEL_GAP, cx + LABEL_GAP, cy - LABEL_GAP, cy + LABEL_GAP));
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every edge is shared by exactly two triangles that run along it in
+    /// opposite directions: the mesh is closed, manifold and consistently wound.
+    fn assert_closed(mesh: &Mesh) {
+        let mut edges = HashMap::new();
+        for &[a, b, c] in &mesh.triangles {
+            for edge in [(a, b), (b, c), (c, a)] {
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1, "edge {:?} is used {} times", (a, b), count);
+            assert_eq!(edges.get(&(b, a)), Some(&1), "edge {:?} has no twin", (a, b));
+        }
+    }
+
+    #[test]
+    fn single_tower_counts() {
+        let mesh = build_city_mesh(&[vec![1]], "");
+        // A 3x3 grid of tops, four tower walls, twelve outer walls and a
+        // bottom fan of twelve.
+        assert_eq!(mesh.triangle_count(), 18 + 8 + 24 + 12);
+        assert_eq!(mesh.vertices.len(), 20 + 13);
+        assert_closed(&mesh);
+
+        let stl = mesh.to_binary_stl("octocat");
+        assert_eq!(u32::from_le_bytes(stl[80..84].try_into().unwrap()), 62);
+        assert_eq!(stl.len(), 84 + 62 * 50);
+
+        let obj = mesh.to_obj("octocat 2024");
+        assert!(obj.contains("\no octocat_2024\n"));
+        assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), 33);
+        assert_eq!(obj.lines().filter(|line| line.starts_with("f ")).count(), 62);
+    }
+
+    #[test]
+    fn city_and_engraved_label_form_one_solid() {
+        let heights = vec![vec![3, 0, 5, 5], vec![0, 1, 0, 30], vec![2, 2, 2, 0]];
+        let mesh = build_city_mesh(&heights, "kx 2024");
+        assert_closed(&mesh);
+
+        let tallest = mesh.vertices.iter().map(|v| v[2]).fold(0.0, f32::max);
+        let lowest_top = mesh.vertices.iter().map(|v| v[2]).filter(|&z| z > 0.0).fold(f32::MAX, f32::min);
+        assert_eq!(tallest, PLATE_THICKNESS + 30.0);
+        assert_eq!(lowest_top, PLATE_THICKNESS - LABEL_DEPTH);
+    }
+
+    #[test]
+    fn heights_follow_the_height_option() {
+        let options = RenderOptions { height: 12, ..RenderOptions::default() };
+        let heights = layout_heights(&[0, 2, 4], &[], "row", &options);
+        assert_eq!(heights, vec![vec![0, 5, 12]]);
+    }
+}
@@ -1,47 +1,57 @@
 use std::fs;
 use colored::*;
+use serde::Serialize;
+use crate::renderer::skyline::RenderOptions;
+use crate::renderer::skyline::insight_lines;
+use crate::statistics::{Insights, Run, Statistics};
 
+/// Saves a rendered skyline; `markdown` cards are written as they are, text
+/// gets the usual header and footer.
 pub fn save_skyline_to_file(
     skyline_output: &str, 
     filename: &str, 
     username: &str, 
     theme: &str,
-    total_contributions: u32
+    total_contributions: u32,
+    format: &str
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut file_content = String::new();
-    
-   
-    file_content.push_str("GitHub Skyline - ASCII Art Generator\n");
-    file_content.push_str(&format!("Username: {}\n", username));
-    file_content.push_str(&format!("Theme: {}\n", theme));
-    file_content.push_str(&format!("Total Contributions: {}\n", total_contributions));
-    file_content.push_str(&"=".repeat(80));
-    file_content.push('\n');
-    file_content.push('\n');
-    
-   
-    let clean_output = strip_ansi_codes(skyline_output);
-    file_content.push_str(&clean_output);
-    
-   
-    file_content.push('\n');
-    file_content.push_str(&"=".repeat(80));
-    file_content.push('\n');
-    file_content.push_str("Generated by GitHub Skyline - Transform your GitHub contributions into ASCII art!\n");
-    file_content.push_str("https://github.com/maverickkamal/github-skyline\n");
+    let file_content = match format {
+        "markdown" => skyline_output.to_string(),
+        _ => {
+            let mut file_content = String::new();
+
+            file_content.push_str("GitHub Skyline - ASCII Art Generator\n");
+            file_content.push_str(&format!("Username: {}\n", username));
+            file_content.push_str(&format!("Theme: {}\n", theme));
+            file_content.push_str(&format!("Total Contributions: {}\n", total_contributions));
+            file_content.push_str(&"=".repeat(80));
+            file_content.push('\n');
+            file_content.push('\n');
+
+            let clean_output = strip_ansi_codes(skyline_output);
+            file_content.push_str(&clean_output);
+
+            file_content.push('\n');
+            file_content.push_str(&"=".repeat(80));
+            file_content.push('\n');
+            file_content.push_str("Generated by GitHub Skyline - Transform your GitHub contributions into ASCII art!\n");
+            file_content.push_str("https://github.com/maverickkamal/github-skyline\n");
+            file_content
+        }
+    };
     
    
     fs::write(filename, file_content)?;
     
-    println!("{}", format!("✅ Skyline saved to: {}", filename).bright_green().bold());
-    println!("{}", format!("📁 File size: {} bytes", fs::metadata(filename)?.len()).bright_blue());
+    eprintln!("{}", format!("✅ Skyline saved to: {}", filename).bright_green().bold());
+    eprintln!("{}", format!("📁 File size: {} bytes", fs::metadata(filename)?.len()).bright_blue());
     
     Ok(())
 }
 
 
 
-fn strip_ansi_codes(text: &str) -> String {
+pub(crate) fn strip_ansi_codes(text: &str) -> String {
    
     let ansi_regex = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
     ansi_regex.replace_all(text, "").to_string()
@@ -50,16 +60,11 @@ fn strip_ansi_codes(text: &str) -> String {
 
 pub fn render_skyline_to_string(
     contributions: &[u32], 
-    _theme: &str,
+    dates: &[String],
     _username: &str,
-    style: &str,
-    scale: &str,
-    ascii_only: bool,
-    sky_mode: &str,
-    width_opt: Option<usize>,
+    options: &RenderOptions,
 ) -> (String, u32) {
-    use crate::renderer::building::{get_max_height, compute_building_heights};
-    use crate::renderer::sky_elements::select_moon_type;
+    use crate::renderer::building::get_max_height;
 
     let mut output = String::new();
     let max_contributions = get_max_height(contributions);
@@ -69,70 +74,24 @@ pub fn render_skyline_to_string(
     output.push_str("║                    🚀 GITHUB SKYLINE GENERATOR 🚀             ║\n");
     output.push_str("╚═══════════════════════════════════════════════════════════════╝\n");
     output.push_str(&format!("📈 Max daily contributions: {}\n", max_contributions));
+    if let Some(reference) = &options.reference {
+        output.push_str(&format!("📏 Shared scale: {} (busiest day {})\n", reference.label, reference.max()));
+    }
     output.push('\n');
 
 
     output.push_str("┌─────────────────────────────────────────────────────────────┐\n");
-    let title = match style.to_lowercase().as_str() {
+    let title = match options.style.to_lowercase().as_str() {
         "ascii" => "│                     ASCII SKYLINE                          │\n",
         "blocks" => "│                   BLOCKS SKYLINE                          │\n",
+        "iso" => "│                ISOMETRIC CONTRIBUTION CITY                 │\n",
+        "heatmap" => "│                 CONTRIBUTION CALENDAR HEATMAP               │\n",
         _ => "│                 BRAILLE-STYLE ASCII SKYLINE                 │\n",
     };
     output.push_str(title);
     output.push_str("└─────────────────────────────────────────────────────────────┘\n");
 
-    
-    let target_height: u32 = 30;
-    let building_heights: Vec<u32> = compute_building_heights(contributions, max_contributions, target_height, scale);
-    let _moon_type = select_moon_type(total_contributions);
-    let max_height = *building_heights.iter().max().unwrap_or(&1) + 6;
-
-    
-    if sky_mode != "none" {
-        for _row in 0..15 {
-            output.push_str("     ");
-            for _col in 0..100 { 
-                if rand::random::<f32>() < 0.05 { output.push('*'); } else { output.push(' '); }
-            }
-            output.push('\n');
-        }
-    }
-
-    
-    let mut width = building_heights.len().min(25);
-    if let Some(w) = width_opt { width = building_heights.len().min(w); }
-    for row in (1..=max_height).rev() {
-        output.push_str("    ");
-        for i in 0..width {
-            let height = building_heights[i];
-            match style.to_lowercase().as_str() {
-                "ascii" => {
-                    if row > height { output.push_str("   "); }
-                    else if row == height { output.push_str("___"); }
-                    else if row == 1 { output.push_str("###"); }
-                    else { output.push_str("###"); }
-                }
-                "blocks" => {
-                    let fill = if ascii_only { '#' } else { '█' };
-                    if row > height { output.push_str("   "); } else { output.push(fill); output.push(fill); output.push(fill); }
-                }
-                _ => {
-                    if row <= height {
-                        if row == 1 { output.push_str("⣸⣸⣸"); }
-                        else if row == height { output.push_str("¯¯¯"); }
-                        else { output.push_str("⣿⣿⣿"); }
-                    } else { output.push_str("   "); }
-                }
-            }
-            if i < width - 1 { output.push(' '); }
-        }
-        output.push('\n');
-    }
-
-    
-    output.push_str("    ");
-    for _i in 0..(width * 3 + width - 1) { output.push('~'); }
-    output.push('\n');
+    output.push_str(&render_plain_skyline(contributions, dates, options));
 
     
     output.push('\n');
@@ -140,10 +99,30 @@ pub fn render_skyline_to_string(
     output.push_str("│               📊 CODING STATISTICS 📊                       │\n");
     output.push_str("├─────────────────────────────────────────────────────────────┤\n");
     output.push_str(&format!("│ 📅 Total days tracked: {:>3}                              │\n", contributions.len()));
-    output.push_str(&format!("│ 🏙️  Days displayed:  {:>3}                                 │\n", contributions.len().min(25)));
+    output.push_str(&format!("│ 🏙️  Days displayed:  {:>3}                                 │\n", options.rendered_days(contributions.len())));
     output.push_str(&format!("│ ⭐ Total contributions: {:>4}                             │\n", total_contributions));
     output.push_str(&format!("│ 🔥 Max daily contributions:  {:>3}                        │\n", max_contributions));
+    let stats = Statistics::new(contributions, dates);
+    for (label, run) in [
+        ("🔥 Longest streak: ", &stats.longest_streak),
+        ("⚡ Current streak: ", &stats.current_streak),
+        ("😴 Longest gap:    ", &stats.longest_gap),
+    ] {
+        let line = match run.range() {
+            Some(range) => format!("│ {} {:>3} days  {}", label, run.days, range),
+            None => format!("│ {} {:>3} days", label, run.days),
+        };
+        let padding = 57usize.saturating_sub(line.chars().count());
+        output.push_str(&format!("{}{}│\n", line, " ".repeat(padding)));
+    }
     output.push_str("╰─────────────────────────────────────────────────────────────╯\n");
+    if stats.streak_at_risk {
+        output.push_str(&format!("⚠️  Streak at risk: no contributions yet today ({}-day streak)\n", stats.current_streak.days));
+    }
+    for line in insight_lines(contributions, dates, options.ascii_only) {
+        output.push_str(&strip_ansi_codes(&line));
+        output.push('\n');
+    }
 
 
     output.push('\n');
@@ -177,3 +156,335 @@ pub fn render_skyline_to_string(
 }
 
 
+/// Renders only the sky, buildings and water, without any decoration or
+/// statistics, so it can be embedded in other documents.
+pub fn render_plain_skyline(contributions: &[u32], dates: &[String], options: &RenderOptions) -> String {
+
+    use crate::renderer::sky_elements::{sky_lines, SkyLayout};
+    use crate::renderer::skyline::{render_iso_lines, render_heatmap_lines, style_glyphs};
+    use crate::renderer::ground::ground_lines;
+    use crate::renderer::theme::get_theme;
+
+    let style = options.style.as_str();
+    let ascii_only = options.ascii_only;
+    let sky_mode = options.sky_mode.as_str();
+    let mut output = String::new();
+
+    let target_height = options.height;
+    let height_scale = options.height_scale(contributions, target_height);
+    let building_heights: Vec<u32> = contributions.iter().map(|&count| height_scale.height(count)).collect();
+    let max_height = target_height;
+    let cell = options.cell;
+
+    
+    let width = options.shown_days(building_heights.len());
+
+    let mut push_lines = |lines: &[String]| {
+        for line in lines {
+            output.push_str(&strip_ansi_codes(line));
+            output.push('\n');
+        }
+    };
+    // Same skies as the terminal: over the whole isometric canvas, and none
+    // over the heatmap calendar.
+    if style.eq_ignore_ascii_case("iso") {
+        let (lines, cols) = render_iso_lines(contributions, dates, options);
+        let layout = SkyLayout::unaligned(contributions, dates, cols, 5, ascii_only);
+        push_lines(&sky_lines(sky_mode, &layout, None, &options.theme, options.seed));
+        push_lines(&lines);
+        return output;
+    }
+    if style.eq_ignore_ascii_case("heatmap") {
+        push_lines(&render_heatmap_lines(contributions, dates, ascii_only, &options.theme));
+        return output;
+    }
+    let layout = SkyLayout::new(contributions, dates, width, cell, 4, ascii_only);
+    push_lines(&sky_lines(sky_mode, &layout, None, &options.theme, options.seed));
+
+    // Days above a `percentile` scale's cap get a broken roof.
+    let offset = building_heights.len() - width;
+    let clipped = |i: usize| contributions[offset + i] > height_scale.cap();
+    let broken = match style.to_lowercase().as_str() {
+        "ascii" => style_glyphs("ascii", ascii_only).broken,
+        "blocks" => style_glyphs("blocks", ascii_only).broken,
+        _ => style_glyphs("braille", ascii_only).broken,
+    };
+    let mut rows = Vec::new();
+    for row in (1..=max_height).rev() {
+        let mut line = String::new();
+        for (i, &height) in building_heights[offset..].iter().enumerate() {
+            match style.to_lowercase().as_str() {
+                _ if row == height && clipped(i) => line.push_str(&cell.stretch(broken)),
+                "ascii" => {
+                    if row > height { line.push_str(&cell.blank()); }
+                    else if row == height { line.push_str(&cell.stretch("___")); }
+                    else { line.push_str(&cell.stretch("###")); }
+                }
+                "blocks" => {
+                    let fill = if ascii_only { "###" } else { "███" };
+                    if row > height { line.push_str(&cell.blank()); } else { line.push_str(&cell.stretch(fill)); }
+                }
+                _ => {
+                    if row <= height {
+                        if row == 1 { line.push_str(&cell.stretch("⣸⣸⣸")); }
+                        else if row == height { line.push_str(&cell.stretch("¯¯¯")); }
+                        else { line.push_str(&cell.stretch("⣿⣿⣿")); }
+                    } else { line.push_str(&cell.blank()); }
+                }
+            }
+            if i < width - 1 { line.push_str(&cell.spacer()); }
+        }
+        rows.push(line);
+    }
+
+    let theme = get_theme(&options.theme);
+    let ground = ground_lines(&options.ground(), &rows, cell.columns(width), &theme, ascii_only, options.seed);
+    for line in rows.iter().chain(&ground) {
+        output.push_str("    ");
+        output.push_str(&strip_ansi_codes(line));
+        output.push('\n');
+    }
+
+    output
+}
+
+
+#[derive(Serialize)]
+struct StatisticsExport<'a> {
+    user: &'a str,
+    from: Option<&'a str>,
+    to: Option<&'a str>,
+    statistics: Statistics,
+    insights: Insights,
+    achievements: Vec<AchievementExport<'a>>,
+}
+
+#[derive(Serialize)]
+struct AchievementExport<'a> {
+    name: &'a str,
+    tier: &'static str,
+    description: &'a str,
+}
+
+/// Statistics and unlocked achievements as JSON, for scripts and dashboards.
+pub fn render_statistics_json(contributions: &[u32], dates: &[String], username: &str) -> String {
+    let achievements = crate::achievements::calculate_achievements(contributions);
+    let export = StatisticsExport {
+        user: username,
+        from: dates.first().map(String::as_str),
+        to: dates.last().map(String::as_str),
+        statistics: Statistics::new(contributions, dates),
+        insights: Insights::new(contributions, dates),
+        achievements: achievements
+            .iter()
+            .map(|a| AchievementExport { name: &a.name, tier: a.tier.label(), description: &a.description })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+/// Statistics as `metric,value` rows; missing dates are left empty.
+pub fn render_statistics_csv(contributions: &[u32], dates: &[String]) -> String {
+    let stats = Statistics::new(contributions, dates);
+    let date = |date: &Option<String>| date.clone().unwrap_or_default();
+    let mut rows = vec![
+        ("days".to_string(), stats.days.to_string()),
+        ("total".to_string(), stats.total.to_string()),
+        ("average".to_string(), format!("{:.2}", stats.average)),
+        ("max_day".to_string(), stats.max_day.to_string()),
+        ("active_days".to_string(), stats.active_days.to_string()),
+        ("active_percent".to_string(), format!("{:.1}", stats.active_percent())),
+    ];
+    for (name, run) in [
+        ("longest_streak", &stats.longest_streak),
+        ("current_streak", &stats.current_streak),
+        ("longest_gap", &stats.longest_gap),
+    ] {
+        rows.push((name.to_string(), run.days.to_string()));
+        rows.push((format!("{}_start", name), date(&run.start)));
+        rows.push((format!("{}_end", name), date(&run.end)));
+    }
+    rows.push(("streak_at_risk".to_string(), stats.streak_at_risk.to_string()));
+
+    let insights = Insights::new(contributions, dates);
+    rows.push(("median".to_string(), insights.median.to_string()));
+    rows.push(("p90".to_string(), insights.p90.to_string()));
+    rows.push(("rolling_7".to_string(), format!("{:.2}", insights.rolling_7)));
+    rows.push(("rolling_30".to_string(), format!("{:.2}", insights.rolling_30)));
+    rows.push(("this_week".to_string(), insights.week_over_week.this_week.to_string()));
+    rows.push(("last_week".to_string(), insights.week_over_week.last_week.to_string()));
+    rows.push((
+        "week_change_percent".to_string(),
+        insights.week_over_week.change_percent.map(|change| format!("{:.1}", change)).unwrap_or_default(),
+    ));
+    for (name, total) in ["sun", "mon", "tue", "wed", "thu", "fri", "sat"].iter().zip(insights.weekdays) {
+        rows.push((format!("weekday_{}", name), total.to_string()));
+    }
+    for month in &insights.months {
+        rows.push((format!("month_{}", month.month), month.total.to_string()));
+    }
+    rows.push((
+        "busiest_month".to_string(),
+        insights.busiest_month.map(|month| month.month).unwrap_or_default(),
+    ));
+    rows.push(("achievements".to_string(), crate::achievements::calculate_achievements(contributions).len().to_string()));
+
+    let mut csv = String::from("metric,value\n");
+    for (metric, value) in rows {
+        csv.push_str(&format!("{},{}\n", metric, value));
+    }
+    csv
+}
+
+pub fn save_export_to_file(content: &str, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(filename, content)?;
+
+    eprintln!("{}", format!("✅ Statistics saved to: {}", filename).bright_green().bold());
+    eprintln!("{}", format!("📁 File size: {} bytes", fs::metadata(filename)?.len()).bright_blue());
+
+    Ok(())
+}
+
+pub const INJECT_START_MARKER: &str = "<!-- skyline:start -->";
+pub const INJECT_END_MARKER: &str = "<!-- skyline:end -->";
+
+/// Builds a README-friendly profile card: the plain skyline in a fenced code
+/// block, followed by a statistics table and the unlocked achievements.
+pub fn render_markdown_card(contributions: &[u32], dates: &[String], username: &str, options: &RenderOptions) -> String {
+    use crate::achievements::calculate_achievements;
+
+    let mut output = String::new();
+    let stats = Statistics::new(contributions, dates);
+    let run = |run: &Run| match run.range() {
+        Some(range) => format!("{} days ({})", run.days, range),
+        None => format!("{} days", run.days),
+    };
+
+    output.push_str(&format!("### 🏙️ {}'s GitHub Skyline\n\n", username));
+    output.push_str("```text\n");
+    output.push_str(&render_plain_skyline(contributions, dates, options));
+    output.push_str("```\n\n");
+
+    output.push_str("| 📊 Statistic | Value |\n");
+    output.push_str("| --- | ---: |\n");
+    output.push_str(&format!("| 📅 Total days tracked | {} |\n", contributions.len()));
+    output.push_str(&format!("| ⭐ Total contributions | {} |\n", stats.total));
+    output.push_str(&format!("| 📈 Average per day | {:.1} |\n", stats.average));
+    output.push_str(&format!("| 🔥 Max daily contributions | {} |\n", stats.max_day));
+    output.push_str(&format!("| 💪 Active coding days | {} ({:.1}%) |\n", stats.active_days, stats.active_percent()));
+    output.push_str(&format!("| 🔥 Longest streak | {} |\n", run(&stats.longest_streak)));
+    let at_risk = if stats.streak_at_risk { " ⚠️ at risk today" } else { "" };
+    output.push_str(&format!("| ⚡ Current streak | {}{} |\n", run(&stats.current_streak), at_risk));
+    output.push_str(&format!("| 😴 Longest gap | {} |\n", run(&stats.longest_gap)));
+
+    let achievements = calculate_achievements(contributions);
+    if !achievements.is_empty() {
+        output.push_str("\n**🏆 Achievements**\n\n");
+        for achievement in &achievements {
+            output.push_str(&format!(
+                "- {} **{}** _({})_ — {}\n",
+                achievement.icon,
+                achievement.name,
+                achievement.tier.label(),
+                achievement.description
+            ));
+        }
+    }
+
+    output.push_str("\n<sub>Generated by [GitHub Skyline](https://github.com/maverickkamal/github-skyline)</sub>\n");
+    output
+}
+
+/// Replaces everything between the skyline markers in an existing README,
+/// keeping the markers so the file can be updated again on the next run.
+pub fn inject_into_readme(markdown: &str, readme_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let readme = fs::read_to_string(readme_path)
+        .map_err(|e| format!("Could not read {}: {}", readme_path, e))?;
+
+    let start = readme
+        .find(INJECT_START_MARKER)
+        .ok_or_else(|| format!("Marker {} not found in {}", INJECT_START_MARKER, readme_path))?;
+    let content_start = start + INJECT_START_MARKER.len();
+    let end = readme[content_start..]
+        .find(INJECT_END_MARKER)
+        .map(|offset| content_start + offset)
+        .ok_or_else(|| format!("Marker {} not found after {} in {}", INJECT_END_MARKER, INJECT_START_MARKER, readme_path))?;
+
+    let mut updated = String::with_capacity(readme.len() + markdown.len());
+    updated.push_str(&readme[..content_start]);
+    updated.push('\n');
+    updated.push_str(markdown.trim_end());
+    updated.push('\n');
+    updated.push_str(&readme[end..]);
+
+    if updated == readme {
+        eprintln!("{}", format!("✅ {} is already up to date", readme_path).bright_green().bold());
+        return Ok(());
+    }
+
+    fs::write(readme_path, updated)?;
+    eprintln!("{}", format!("✅ Skyline injected into: {}", readme_path).bright_green().bold());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::skyline::DEFAULT_WIDTH;
+
+    fn sample() -> (Vec<u32>, Vec<String>) {
+        let contributions = vec![0, 3, 5, 0, 0, 2, 8, 1, 0, 4];
+        let dates = (1..=10).map(|day| format!("2024-03-{:02}", day)).collect();
+        (contributions, dates)
+    }
+
+    #[test]
+    fn same_seed_renders_the_same_skyline() {
+        let (contributions, dates) = sample();
+        let render = |seed| render_plain_skyline(&contributions, &dates, &RenderOptions::default().with_seed(seed));
+        assert_eq!(render(7), render(7));
+        assert_ne!(render(7), render(8));
+    }
+
+    #[test]
+    fn days_displayed_counts_the_days_drawn() {
+        let contributions: Vec<u32> = (0..40).map(|day| day % 5).collect();
+        let dates: Vec<String> = (0..40).map(|day| format!("2024-03-{:02}", day % 28 + 1)).collect();
+        let displayed = |options: RenderOptions| {
+            let (output, _) = render_skyline_to_string(&contributions, &dates, "octocat", &options);
+            let line = output.lines().find(|line| line.contains("Days displayed")).unwrap().to_string();
+            line.split_whitespace().nth(4).unwrap().parse::<usize>().unwrap()
+        };
+        assert_eq!(displayed(RenderOptions::default()), DEFAULT_WIDTH);
+        assert_eq!(displayed(RenderOptions { width: Some(10), ..Default::default() }), 10);
+        assert_eq!(displayed(RenderOptions { style: "heatmap".to_string(), ..Default::default() }), 40);
+    }
+
+    #[test]
+    fn statistics_json_parses() {
+        let (contributions, dates) = sample();
+        let json: serde_json::Value = serde_json::from_str(&render_statistics_json(&contributions, &dates, "octocat")).unwrap();
+        assert_eq!(json["user"], "octocat");
+        assert_eq!(json["from"], "2024-03-01");
+        assert_eq!(json["to"], "2024-03-10");
+        assert_eq!(json["statistics"]["total"], 23);
+        assert_eq!(json["statistics"]["longest_streak"]["start"], "2024-03-06");
+        assert_eq!(json["insights"]["busiest_month"]["month"], "2024-03");
+    }
+
+    #[test]
+    fn statistics_json_parses_without_data() {
+        let json: serde_json::Value = serde_json::from_str(&render_statistics_json(&[], &[], "octocat")).unwrap();
+        assert_eq!(json["statistics"]["total"], 0);
+    }
+
+    #[test]
+    fn statistics_csv_has_one_value_per_row() {
+        let (contributions, dates) = sample();
+        let csv = render_statistics_csv(&contributions, &dates);
+        assert!(csv.starts_with("metric,value\n"));
+        assert!(csv.lines().all(|line| line.split(',').count() == 2));
+        assert!(csv.contains("\ntotal,23\n"));
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::time::{Duration, Instant};
+use colored::*;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, style::Print, terminal};
+use crate::renderer::sky_elements::{moon_for, sky_lines, SkyLayout};
+use crate::renderer::skyline::{building_frame, building_lines, RenderOptions};
+
+const RISE_STAGGER: u32 = 2;
+const LIGHT_STAGGER: u32 = 1;
+
+/// Plays the city being constructed in the alternate screen: buildings rise
+/// floor by floor from left to right, then their windows light up floor by
+/// floor, building after building, while the sky comes out row by row. Any
+/// key skips to the end; Ctrl+C restores the terminal and exits. Does nothing
+/// when stdout is not a terminal, so piped output stays static, or when the
+/// city does not fit on the screen.
+pub fn animate_skyline(contributions: &[u32], dates: &[String], options: &RenderOptions, fps: u32) {
+    if contributions.is_empty() || !io::stdout().is_terminal() {
+        return;
+    }
+    let width = options.shown_days(contributions.len());
+    let layout = SkyLayout::new(contributions, dates, width, options.cell, 0, options.ascii_only);
+    let moon_type = moon_for(&options.moon, contributions.iter().sum(), dates);
+    let sky = sky_lines(&options.sky_mode, &layout, Some(&moon_type), &options.theme, options.seed);
+
+    // The finished city plus a status line has to fit, or frames would
+    // scroll past the top of the screen.
+    let rows = sky.len() + building_lines(contributions, dates, options, width).len() + 1;
+    let columns = options.cell.columns(width);
+    match terminal::size() {
+        Ok((screen_columns, screen_rows)) if rows <= screen_rows as usize && columns <= screen_columns as usize => {}
+        _ => {
+            eprintln!("{}", "⚠️  The city does not fit in the terminal, skipping the animation".bright_yellow());
+            return;
+        }
+    }
+    if terminal::enable_raw_mode().is_err() {
+        return;
+    }
+
+    let mut stdout = io::stdout();
+    let result = play(&mut stdout, contributions, dates, options, &sky, width, fps.clamp(1, 120));
+
+    let _ = execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    match result {
+        Ok(Playback::Interrupted) => std::process::exit(130),
+        Ok(Playback::Finished) => {}
+        Err(e) => eprintln!("{}", format!("⚠️  Animation stopped: {}", e).bright_yellow()),
+    }
+}
+
+enum Playback {
+    Finished,
+    /// Ctrl+C, which raw mode delivers as a key instead of a signal.
+    Interrupted,
+}
+
+fn play(
+    stdout: &mut Stdout,
+    contributions: &[u32],
+    dates: &[String],
+    options: &RenderOptions,
+    sky: &[String],
+    width: usize,
+    fps: u32,
+) -> io::Result<Playback> {
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+    let scale = options.height_scale(contributions, options.height);
+    let tallest = contributions[contributions.len() - width..].iter().map(|&count| scale.height(count)).max().unwrap_or(0);
+    let rise_end = (width as u32).saturating_sub(1) * RISE_STAGGER + tallest;
+    let lights_end = rise_end + (width as u32).saturating_sub(1) * LIGHT_STAGGER + tallest;
+    let sky_end = rise_end + sky.len() as u32;
+    let total_frames = lights_end.max(sky_end) + fps / 2;
+    let frame_time = Duration::from_secs_f64(1.0 / fps as f64);
+
+    for frame in 0..=total_frames {
+        let started = Instant::now();
+
+        let revealed = frame.saturating_sub(rise_end) as usize;
+        for (row, line) in sky.iter().enumerate() {
+            let line = if row < revealed { line.as_str() } else { "" };
+            queue!(stdout, cursor::MoveTo(0, row as u16), Print(line), terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+
+        let built = |day: usize, height: u32| frame.saturating_sub(day as u32 * RISE_STAGGER).min(height);
+        let lit = |day: usize, height: u32| frame.saturating_sub(rise_end + day as u32 * LIGHT_STAGGER).min(height);
+        let city = building_frame(contributions, dates, options, width, built, lit);
+        for (offset, line) in city.iter().enumerate() {
+            let y = (sky.len() + offset) as u16;
+            queue!(stdout, cursor::MoveTo(0, y), Print(line), terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+
+        let status_row = (sky.len() + city.len()) as u16;
+        let status = "🏗️  Building your skyline... press any key to skip, Ctrl+C to quit".bright_cyan().to_string();
+        queue!(stdout, cursor::MoveTo(0, status_row), Print(status), terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        stdout.flush()?;
+
+        match key_pressed(frame_time.saturating_sub(started.elapsed()))? {
+            Some(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(Playback::Interrupted);
+            }
+            Some(_) => break,
+            None => {}
+        }
+    }
+
+    Ok(Playback::Finished)
+}
+
+/// Waits up to `timeout` for a key press.
+fn key_pressed(timeout: Duration) -> io::Result<Option<KeyEvent>> {
+    if event::poll(timeout)?
+        && let Event::Key(key) = event::read()?
+        && key.kind == KeyEventKind::Press
+    {
+        return Ok(Some(key));
+    }
+    Ok(None)
+}
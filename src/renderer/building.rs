@@ -3,28 +3,185 @@ pub fn get_max_height(contributions: &[u32]) -> u32 {
 } 
 
 
-pub fn scale_height(contribution_count: u32, max_contributions: u32, target_height: u32, scale_name: &str) -> u32 {
+/// Accepted `--scale` values, for error messages and help.
+pub const SCALE_NAMES: &str = "linear, sqrt, log, dramatic, rank, pow:<k>, percentile:<p>";
+
+/// How contribution counts become building heights.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scale {
+    Linear,
+    Sqrt,
+    Log,
+    /// `count^1.2`, the default.
+    Dramatic,
+    /// `count^k` for a custom `k`.
+    Pow(f32),
+    /// Linear up to the `p`-th percentile of the active days; busier days are
+    /// clipped to full height, so one bulk import cannot flatten the city.
+    Percentile(f32),
+    /// Evenly spaced by rank among the active days, ignoring magnitudes.
+    Rank,
+}
+
+impl Scale {
+    /// Parses a `--scale` value such as `sqrt`, `pow:0.7` or `percentile:95`.
+    pub fn parse(name: &str) -> Result<Scale, String> {
+        let name = name.trim().to_lowercase();
+        let (kind, value) = match name.split_once(':') {
+            Some((kind, value)) => (kind, Some(value.trim())),
+            None => (name.as_str(), None),
+        };
+        let number = |example: &str| {
+            value
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| format!("Scale '{}' needs a number, e.g. {}:{}.", kind, kind, example))
+        };
+        match (kind, value) {
+            ("linear", None) => Ok(Scale::Linear),
+            ("sqrt", None) => Ok(Scale::Sqrt),
+            ("log", None) => Ok(Scale::Log),
+            ("dramatic" | "exp", None) => Ok(Scale::Dramatic),
+            ("rank", None) => Ok(Scale::Rank),
+            ("pow", _) => match number("0.7")? {
+                k if k > 0.0 => Ok(Scale::Pow(k)),
+                _ => Err("Scale pow:<k> needs k greater than 0.".to_string()),
+            },
+            ("percentile", _) => match number("95")? {
+                p if p > 0.0 && p <= 100.0 => Ok(Scale::Percentile(p)),
+                _ => Err("Scale percentile:<p> needs p between 0 and 100.".to_string()),
+            },
+            _ => Err(format!("Unknown scale '{}'. Use {}.", name, SCALE_NAMES)),
+        }
+    }
+}
+
+impl std::fmt::Display for Scale {
+    /// The `--scale` value that parses back to this scale.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Scale::Linear => write!(f, "linear"),
+            Scale::Sqrt => write!(f, "sqrt"),
+            Scale::Log => write!(f, "log"),
+            Scale::Dramatic => write!(f, "dramatic"),
+            Scale::Pow(k) => write!(f, "pow:{}", k),
+            Scale::Percentile(p) => write!(f, "percentile:{}", p),
+            Scale::Rank => write!(f, "rank"),
+        }
+    }
+}
+
+/// Turns contribution counts into building heights. `percentile` and `rank`
+/// depend on every active day, not only the busiest, so the scale is built
+/// once from all of them.
+pub struct HeightScale {
+    scale: Scale,
+    target_height: u32,
+    /// Contributions of the reference's active days, ascending.
+    active: Vec<u32>,
+}
+
+impl HeightScale {
+    /// Scales against `reference`: usually the rendered days themselves, or
+    /// a `ScaleReference` shared between renders so they line up.
+    pub fn new(reference: &[u32], target_height: u32, scale: Scale) -> Self {
+        let mut active: Vec<u32> = reference.iter().copied().filter(|&c| c > 0).collect();
+        active.sort_unstable();
+        HeightScale {
+            scale,
+            target_height,
+            active,
+        }
+    }
+
+    /// The busiest count that still fits: the `percentile` cap, otherwise the
+    /// reference's busiest day. Busier days stand at full height.
+    pub fn cap(&self) -> u32 {
+        let Some(&max) = self.active.last() else {
+            return 0;
+        };
+        match self.scale {
+            Scale::Percentile(p) => {
+                let rank = (p / 100.0 * self.active.len() as f32).ceil() as usize;
+                self.active[rank.clamp(1, self.active.len()) - 1]
+            }
+            _ => max,
+        }
+    }
+
+    pub fn height(&self, count: u32) -> u32 {
+        if count == 0 || self.target_height == 0 {
+            return 0;
+        }
+        let cap = self.cap();
+        match self.scale {
+            Scale::Percentile(_) => curve(count.min(cap), cap, self.target_height, Scale::Linear),
+            Scale::Rank => {
+                let rank = self.active.partition_point(|&c| c <= count);
+                let fraction = rank as f32 / self.active.len().max(1) as f32;
+                ((fraction * self.target_height as f32).round() as u32).max(1)
+            }
+            scale => curve(count.min(cap), cap, self.target_height, scale),
+        }
+    }
+}
+
+/// Contributions that every render scales against instead of its own, from
+/// `--max` or `--scale-ref`, so separate skylines can be compared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScaleReference {
+    /// Where the reference came from, for the header and legend.
+    pub label: String,
+    pub contributions: Vec<u32>,
+}
+
+impl ScaleReference {
+    /// `--max <n>`: a single day of `n` contributions.
+    pub fn fixed(max: u32) -> Self {
+        ScaleReference { label: "--max".to_string(), contributions: vec![max] }
+    }
+
+    /// Reads contribution counts separated by whitespace or commas; a file
+    /// holding a single number works like `--max`.
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read scale reference {}: {}", path, e))?;
+        let contributions = text
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .map(|word| word.parse::<u32>().map_err(|_| format!("Invalid contribution count '{}' in {}", word, path)))
+            .collect::<Result<Vec<u32>, String>>()?;
+        Ok(ScaleReference { label: path.to_string(), contributions })
+    }
+
+    pub fn max(&self) -> u32 {
+        self.contributions.iter().copied().max().unwrap_or(0)
+    }
+}
+
+/// Height from a fixed curve over `count / max_contributions`.
+fn curve(contribution_count: u32, max_contributions: u32, target_height: u32, scale: Scale) -> u32 {
     if max_contributions == 0 || contribution_count == 0 || target_height == 0 {
         return 0;
     }
 
     let normalized = contribution_count as f32 / max_contributions as f32;
-    let scaled = match scale_name.to_lowercase().as_str() {
-        "linear" => normalized,
-        "sqrt" => normalized.sqrt(),
-        "log" => {
+    let scaled = match scale {
+        Scale::Linear => normalized,
+        Scale::Sqrt => normalized.sqrt(),
+        Scale::Log => {
             let num = (1.0 + contribution_count as f32).ln();
             let den = (1.0 + max_contributions as f32).ln();
             if den > 0.0 { num / den } else { 0.0 }
         }
-        "exp" | "dramatic" => normalized.powf(1.2),
+        Scale::Pow(k) => normalized.powf(k),
         _ => normalized.powf(1.2),
     };
 
     let mut result = (scaled * (target_height as f32)).round() as u32;
     if contribution_count > 0 {
-        let min_nonzero = match scale_name.to_lowercase().as_str() {
-            "linear" | "sqrt" | "log" => 1,
+        let min_nonzero = match scale {
+            Scale::Linear | Scale::Sqrt | Scale::Log | Scale::Pow(_) => 1,
             _ => 2, 
         };
         if result < min_nonzero { result = min_nonzero; }
@@ -33,9 +190,158 @@ pub fn scale_height(contribution_count: u32, max_contributions: u32, target_heig
 }
 
 
-pub fn compute_building_heights(contributions: &[u32], max_contributions: u32, target_height: u32, scale_name: &str) -> Vec<u32> {
-    contributions
-        .iter()
-        .map(|&count| scale_height(count, max_contributions, target_height, scale_name))
-        .collect()
-}
\ No newline at end of file
+/// Upper bounds of GitHub's first three contribution levels, taken from the
+/// quartiles of the non-zero days.
+pub fn level_thresholds(contributions: &[u32]) -> [u32; 3] {
+    let mut active: Vec<u32> = contributions.iter().copied().filter(|&c| c > 0).collect();
+    if active.is_empty() {
+        return [0, 0, 0];
+    }
+    active.sort_unstable();
+    let quartile = |q: usize| active[((active.len() - 1) * q) / 4];
+    [quartile(1), quartile(2), quartile(3)]
+}
+
+/// GitHub-style contribution level: 0 for no contributions, then 1 to 4.
+pub fn contribution_level(count: u32, thresholds: &[u32; 3]) -> usize {
+    match count {
+        0 => 0,
+        c if c <= thresholds[0] => 1,
+        c if c <= thresholds[1] => 2,
+        c if c <= thresholds[2] => 3,
+        _ => 4,
+    }
+}
+
+/// Columns per building and blank columns between neighbours, from
+/// `--cell-width` and `--gap`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellSize {
+    pub width: usize,
+    pub gap: usize,
+}
+
+impl Default for CellSize {
+    fn default() -> Self {
+        CellSize { width: 3, gap: 1 }
+    }
+}
+
+impl CellSize {
+    /// Columns from one building's left edge to the next one's.
+    pub fn pitch(&self) -> usize {
+        self.width + self.gap
+    }
+
+    /// Columns taken by `buildings` standing side by side.
+    pub fn columns(&self, buildings: usize) -> usize {
+        (buildings * self.pitch()).saturating_sub(self.gap)
+    }
+
+    pub fn blank(&self) -> String {
+        " ".repeat(self.width)
+    }
+
+    pub fn spacer(&self) -> String {
+        " ".repeat(self.gap)
+    }
+
+    /// Fits a three-column pattern to the cell by keeping its first glyph and
+    /// repeating the rest, so `| |` becomes `| | |` and `/\/` becomes `/\/\/`.
+    pub fn stretch(&self, glyphs: &str) -> String {
+        let chars: Vec<char> = glyphs.chars().collect();
+        match chars.split_first() {
+            Some((first, rest)) if !rest.is_empty() => {
+                std::iter::once(*first).chain(rest.iter().copied().cycle()).take(self.width).collect()
+            }
+            _ => chars.iter().copied().cycle().take(self.width).collect(),
+        }
+    }
+
+    /// Fits a pattern to the cell by padding or trimming both sides evenly,
+    /// for art with a single centerpiece such as an antenna.
+    pub fn center(&self, glyphs: &str) -> String {
+        let chars: Vec<char> = glyphs.chars().collect();
+        if self.width >= chars.len() {
+            let left = (self.width - chars.len()) / 2;
+            format!("{}{}{}", " ".repeat(left), glyphs, " ".repeat(self.width - chars.len() - left))
+        } else {
+            let start = (chars.len() - self.width) / 2;
+            chars[start..start + self.width].iter().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scale_names() {
+        assert_eq!(Scale::parse("linear"), Ok(Scale::Linear));
+        assert_eq!(Scale::parse(" SQRT "), Ok(Scale::Sqrt));
+        assert_eq!(Scale::parse("log"), Ok(Scale::Log));
+        assert_eq!(Scale::parse("exp"), Ok(Scale::Dramatic));
+        assert_eq!(Scale::parse("rank"), Ok(Scale::Rank));
+        assert_eq!(Scale::parse("pow:0.7"), Ok(Scale::Pow(0.7)));
+        assert_eq!(Scale::parse("percentile: 95"), Ok(Scale::Percentile(95.0)));
+        assert_eq!(Scale::parse("percentile:100"), Ok(Scale::Percentile(100.0)));
+    }
+
+    #[test]
+    fn rejects_bad_scales() {
+        for name in [
+            "", "cubic", "linear:2", "pow", "pow:", "pow:0", "pow:-1", "pow:abc", "pow:inf",
+            "percentile", "percentile:0", "percentile:101", "percentile:nan",
+        ] {
+            assert!(Scale::parse(name).is_err(), "{} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn display_parses_back() {
+        for scale in [
+            Scale::Linear, Scale::Sqrt, Scale::Log, Scale::Dramatic, Scale::Rank,
+            Scale::Pow(0.7), Scale::Percentile(95.0), Scale::Percentile(99.5),
+        ] {
+            assert_eq!(Scale::parse(&scale.to_string()), Ok(scale));
+        }
+    }
+
+    #[test]
+    fn cap_without_active_days() {
+        assert_eq!(HeightScale::new(&[], 30, Scale::Dramatic).cap(), 0);
+        assert_eq!(HeightScale::new(&[0, 0, 0], 30, Scale::Percentile(95.0)).cap(), 0);
+        assert_eq!(HeightScale::new(&[0, 0], 30, Scale::Linear).height(0), 0);
+    }
+
+    #[test]
+    fn cap_is_the_busiest_day_or_the_percentile() {
+        let counts: Vec<u32> = (0..=10).collect();
+        assert_eq!(HeightScale::new(&counts, 30, Scale::Dramatic).cap(), 10);
+        assert_eq!(HeightScale::new(&counts, 30, Scale::Percentile(100.0)).cap(), 10);
+        assert_eq!(HeightScale::new(&counts, 30, Scale::Percentile(50.0)).cap(), 5);
+        assert_eq!(HeightScale::new(&counts, 30, Scale::Percentile(0.1)).cap(), 1);
+    }
+
+    #[test]
+    fn heights_reach_the_target() {
+        let counts = [0, 1, 5, 10];
+        for scale in [Scale::Linear, Scale::Sqrt, Scale::Log, Scale::Dramatic, Scale::Rank, Scale::Pow(2.0)] {
+            let heights = HeightScale::new(&counts, 30, scale);
+            assert_eq!(heights.height(0), 0, "{}", scale);
+            assert!(heights.height(1) >= 1, "{}", scale);
+            assert_eq!(heights.height(10), 30, "{}", scale);
+        }
+    }
+
+    #[test]
+    fn percentile_clips_busier_days() {
+        let counts = [1, 2, 3, 4, 100];
+        let heights = HeightScale::new(&counts, 20, Scale::Percentile(80.0));
+        assert_eq!(heights.cap(), 4);
+        assert_eq!(heights.height(4), 20);
+        assert_eq!(heights.height(100), 20);
+        assert_eq!(heights.height(2), 10);
+    }
+}
@@ -0,0 +1,158 @@
+use colored::Color;
+use crate::calendar::{month_of, weekday};
+use crate::renderer::building::{contribution_level, level_thresholds};
+use crate::renderer::theme::{color_to_rgb, get_theme, Paint, Theme};
+
+pub const COLOR_MODES: [&str; 5] = ["height", "gradient", "weekday", "month", "level"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum ColorMode {
+    Height,
+    Gradient,
+    Weekday,
+    Month,
+    Level,
+}
+
+/// Picks building and window colors from the theme's ramps according to
+/// `--color-by`. Heights are normalized against `target_height`, so the
+/// buckets work for any building height.
+pub(crate) struct BuildingColors {
+    pub(crate) theme: Theme,
+    mode: ColorMode,
+    target_height: u32,
+    thresholds: [u32; 3],
+    lights: bool,
+    /// Floors of each shown day whose lights are on so far, while the
+    /// animation switches the city on; `None` lights every floor.
+    lit_floors: Option<Vec<u32>>,
+    cap: Option<u32>,
+}
+
+impl BuildingColors {
+    pub(crate) fn new(theme: &str, color_by: &str, contributions: &[u32], target_height: u32) -> Self {
+        let mode = match color_by.to_lowercase().as_str() {
+            "gradient" => ColorMode::Gradient,
+            "weekday" => ColorMode::Weekday,
+            "month" => ColorMode::Month,
+            "level" => ColorMode::Level,
+            _ => ColorMode::Height,
+        };
+        BuildingColors {
+            theme: get_theme(theme),
+            mode,
+            target_height: target_height.max(1),
+            thresholds: level_thresholds(contributions),
+            lights: true,
+            lit_floors: None,
+            cap: None,
+        }
+    }
+
+    /// Switches the lights off for `--sky day`, so no window is lit.
+    pub(crate) fn daylight(mut self, day: bool) -> Self {
+        self.lights = !day;
+        self
+    }
+
+    /// Switches on only the lowest `floors[i]` floors of the `i`-th shown day.
+    pub(crate) fn lit_floors(mut self, floors: Vec<u32>) -> Self {
+        self.lit_floors = Some(floors);
+        self
+    }
+
+    /// Whether the window on `row` of the `day`-th shown building is lit: the
+    /// day had contributions, it is not daytime and the floor's lights are on.
+    pub(crate) fn lit(&self, count: u32, day: usize, row: u32) -> bool {
+        self.lights
+            && count > 0
+            && self.lit_floors.as_ref().is_none_or(|floors| floors.get(day).is_some_and(|&lit| row <= lit))
+    }
+
+    /// Marks days above `cap` contributions as clipped, for `--scale percentile:<p>`.
+    pub(crate) fn clipped_above(mut self, cap: Option<u32>) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Whether a day is busier than the scale shows and gets a broken roof.
+    pub(crate) fn clipped(&self, count: u32) -> bool {
+        self.cap.is_some_and(|cap| count > cap)
+    }
+
+    pub(crate) fn target_height(&self) -> u32 {
+        self.target_height
+    }
+
+    /// Which of `bands` equal height bands a building falls in, lowest first.
+    pub(crate) fn height_band(&self, height: u32, bands: usize) -> usize {
+        height_bucket(height, self.target_height, bands)
+    }
+
+    pub(crate) fn building(&self, height: u32, count: u32, date: Option<&str>) -> Paint {
+        self.pick(&self.theme.building_colors, height, count, date, 0)
+    }
+
+    /// One ramp step darker than `building`, for shaded faces; at the dark
+    /// end of the ramp one step lighter instead, so the faces still differ.
+    pub(crate) fn building_shade(&self, height: u32, count: u32, date: Option<&str>) -> Paint {
+        let darker = self.pick(&self.theme.building_colors, height, count, date, -1);
+        if darker != self.building(height, count, date) {
+            darker
+        } else {
+            self.pick(&self.theme.building_colors, height, count, date, 1)
+        }
+    }
+
+    pub(crate) fn window(&self, height: u32, count: u32, date: Option<&str>) -> Paint {
+        self.pick(&self.theme.window_colors, height, count, date, 0)
+    }
+
+    /// The ramp color for a building, moved `steps` along the ramp; negative
+    /// steps are darker.
+    fn pick(&self, ramp: &[Paint], height: u32, count: u32, date: Option<&str>, steps: isize) -> Paint {
+        let position = match self.mode {
+            ColorMode::Height => {
+                let bucket = height_bucket(height, self.target_height, ramp.len()) as isize;
+                return ramp[(bucket + steps).clamp(0, ramp.len() as isize - 1) as usize];
+            }
+            ColorMode::Gradient => Some(height as f32 / self.target_height as f32),
+            ColorMode::Weekday => date.and_then(weekday).map(|d| d as f32 / 6.0),
+            ColorMode::Month => date.and_then(month_of).map(|m| (m - 1) as f32 / 11.0),
+            ColorMode::Level => {
+                let level = contribution_level(count, &self.thresholds).max(1);
+                Some((level - 1) as f32 / 3.0)
+            }
+        };
+        // Days without a date fall back to their height.
+        let position = position.unwrap_or(height as f32 / self.target_height as f32);
+        let step = if ramp.len() > 1 { 1.0 / (ramp.len() - 1) as f32 } else { 0.0 };
+        sample_ramp(ramp, position + step * steps as f32)
+    }
+}
+
+/// Splits `1..=target_height` into `buckets` equal bands, lowest first.
+pub(crate) fn height_bucket(height: u32, target_height: u32, buckets: usize) -> usize {
+    if height == 0 || buckets == 0 {
+        return 0;
+    }
+    ((height as usize - 1) * buckets / target_height.max(1) as usize).min(buckets - 1)
+}
+
+/// Color at `position` (0-1) along the ramp, blending neighbouring stops.
+pub(crate) fn sample_ramp(ramp: &[Paint], position: f32) -> Paint {
+    let scaled = position.clamp(0.0, 1.0) * (ramp.len() - 1) as f32;
+    let index = scaled.floor() as usize;
+    let next = (index + 1).min(ramp.len() - 1);
+    let fraction = scaled - index as f32;
+    if fraction < 0.01 || index == next {
+        return ramp[index];
+    }
+
+    let (from, to) = (color_to_rgb(ramp[index].color), color_to_rgb(ramp[next].color));
+    let [r, g, b] = [0, 1, 2].map(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * fraction).round() as u8);
+    Paint {
+        color: Color::TrueColor { r, g, b },
+        bold: if fraction < 0.5 { ramp[index].bold } else { ramp[next].bold },
+    }
+}
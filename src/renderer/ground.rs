@@ -0,0 +1,215 @@
+use colored::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use crate::renderer::theme::{Paint, Theme};
+
+/// Foregrounds for `--ground` and a theme's `ground` field.
+pub const GROUND_MODES: [&str; 6] = ["water", "reflection", "street", "park", "bridge", "none"];
+
+/// Skyline rows mirrored into the water by the `reflection` ground.
+const REFLECTION_ROWS: usize = 6;
+/// Columns between two bridge piers.
+const BRIDGE_SPAN: usize = 12;
+
+const LEAVES: Paint = Paint { color: Color::Green, bold: true };
+const GRASS: Paint = Paint { color: Color::BrightGreen, bold: false };
+
+/// The rows drawn under the buildings, `width` columns wide. `skyline` is the
+/// rendered building rows, top to bottom, which the reflection mirrors.
+pub(crate) fn ground_lines(mode: &str, skyline: &[String], width: usize, theme: &Theme, ascii: bool, seed: u64) -> Vec<String> {
+    match mode {
+        "none" => Vec::new(),
+        "reflection" => {
+            let mut lines = vec![water_line(width, theme)];
+            let mirrored = skyline.iter().rev().take(REFLECTION_ROWS);
+            lines.extend(mirrored.enumerate().map(|(depth, line)| reflect_line(line, depth, width, theme)));
+            lines
+        }
+        "street" => street_lines(width, theme, ascii, seed),
+        "park" => park_lines(width, theme, ascii, seed),
+        "bridge" => bridge_lines(width, theme, ascii),
+        _ => vec![water_line(width, theme)],
+    }
+}
+
+/// Legend swatch and label for a ground, or `None` when nothing is drawn.
+pub(crate) fn ground_swatch(mode: &str, theme: &Theme, ascii: bool) -> Option<(String, &'static str)> {
+    let water: String = (0..3).map(|i| theme.water_colors[i % theme.water_colors.len()].paint("~").to_string()).collect();
+    let swatch = match mode {
+        "none" => return None,
+        "reflection" => (water, "Water (mirrors the bottom of the skyline)"),
+        "street" => (theme.star_colors[0].paint(car_glyph(ascii)).to_string(), "Street"),
+        "park" => {
+            let tree = LEAVES.paint(if ascii { "&" } else { "♣" });
+            (format!("{}{}{}", tree, GRASS.paint(","), tree), "Park")
+        }
+        "bridge" => (theme.base_color.paint(if ascii { "=+=" } else { "═╦═" }).to_string(), "Bridge"),
+        _ => (water, "Water"),
+    };
+    Some(swatch)
+}
+
+fn water_line(width: usize, theme: &Theme) -> String {
+    (0..width).map(|i| theme.water_colors[i % theme.water_colors.len()].paint("~").to_string()).collect()
+}
+
+/// One reflected row, `depth` rows below the waterline: the glyphs flipped
+/// upside down and dimmed, shifted side to side and broken up by ripples
+/// that get denser further down.
+fn reflect_line(line: &str, depth: usize, width: usize, theme: &Theme) -> String {
+    let cells = cells(line);
+    let shift = [0, 1, 0, -1][depth % 4];
+    let ripple_every = 7 - depth.min(4);
+    let mut reflected = String::new();
+    for col in 0..width {
+        let cell = col.checked_add_signed(-shift).and_then(|source| cells.get(source));
+        match cell {
+            Some((_, ' ')) | None => reflected.push(' '),
+            Some(_) if (col + 3 * depth).is_multiple_of(ripple_every) => {
+                let water = theme.water_colors[col % theme.water_colors.len()];
+                reflected.push_str(&water.paint("~").dimmed().to_string());
+            }
+            // Without colors there is no escape code to dim.
+            Some((style, glyph)) if style.is_empty() => reflected.push(flip_glyph(*glyph)),
+            Some((style, glyph)) => reflected.push_str(&format!("{}\x1b[22;2m{}\x1b[0m", style, flip_glyph(*glyph))),
+        }
+    }
+    reflected
+}
+
+/// Splits a rendered line into its visible characters, each with the escape
+/// codes in effect for it.
+fn cells(line: &str) -> Vec<(String, char)> {
+    let mut cells = Vec::new();
+    let mut style = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            let code: String = chars.by_ref().take_while(|&c| c != 'm').collect();
+            if code == "[0" {
+                style.clear();
+            } else {
+                style.push_str(&format!("\x1b{}m", code));
+            }
+        } else {
+            cells.push((style.clone(), c));
+        }
+    }
+    cells
+}
+
+/// The glyph upside down, for the shapes the building styles draw with.
+fn flip_glyph(glyph: char) -> char {
+    match glyph {
+        '▀' => '▄',
+        '▄' => '▀',
+        '¯' | '‾' => '_',
+        '_' => '‾',
+        '/' => '\\',
+        '\\' => '/',
+        '^' => 'v',
+        'A' => 'V',
+        '▲' => '▼',
+        '╤' => '╧',
+        '▟' => '▜',
+        '▙' => '▛',
+        '\u{2800}'..='\u{28ff}' => flip_braille(glyph),
+        _ => glyph,
+    }
+}
+
+/// Mirrors a braille cell's dots top to bottom.
+fn flip_braille(glyph: char) -> char {
+    // Dot bits of the left and right columns, top to bottom.
+    const COLUMNS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+    let dots = glyph as u32 - 0x2800;
+    let mut flipped = 0;
+    for column in COLUMNS {
+        for (row, bit) in column.iter().enumerate() {
+            if dots & bit != 0 {
+                flipped |= column[3 - row];
+            }
+        }
+    }
+    char::from_u32(0x2800 + flipped).unwrap_or(glyph)
+}
+
+fn car_glyph(ascii: bool) -> &'static str {
+    if ascii { "[==]" } else { "▟██▙" }
+}
+
+/// A sidewalk and a two-lane road with seeded traffic.
+fn street_lines(width: usize, theme: &Theme, ascii: bool, seed: u64) -> Vec<String> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (curb, dash) = if ascii { ('-', '-') } else { ('▔', '─') };
+    let sidewalk: String = std::iter::repeat_n(curb, width).collect();
+    let markings: String = (0..width).map(|col| if col % 4 < 2 { dash } else { ' ' }).collect();
+    vec![
+        theme.base_color.paint(&sidewalk).to_string(),
+        traffic_lane(width, car_glyph(ascii), theme, &mut rng),
+        theme.moon_color.paint(&markings).to_string(),
+        traffic_lane(width, car_glyph(ascii), theme, &mut rng),
+    ]
+}
+
+fn traffic_lane(width: usize, car: &str, theme: &Theme, rng: &mut ChaCha8Rng) -> String {
+    let car_width = car.chars().count();
+    let mut col = rng.gen_range(0..6).min(width);
+    let mut lane = " ".repeat(col);
+    while col + car_width <= width {
+        let paint = theme.star_colors[rng.gen_range(0..theme.star_colors.len())];
+        lane.push_str(&paint.paint(car).to_string());
+        let gap = rng.gen_range(3..12).min(width - col - car_width);
+        lane.push_str(&" ".repeat(gap));
+        col += car_width + gap;
+    }
+    lane.push_str(&" ".repeat(width - col));
+    lane
+}
+
+/// Seeded trees and flowers on a strip of grass.
+fn park_lines(width: usize, theme: &Theme, ascii: bool, seed: u64) -> Vec<String> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let tree = if ascii { "&" } else { "♣" };
+    let mut trees = String::new();
+    for _ in 0..width {
+        if rng.gen_bool(0.3) {
+            trees.push_str(&LEAVES.paint(tree).to_string());
+        } else if rng.gen_bool(0.1) {
+            let flower = theme.star_colors[rng.gen_range(0..theme.star_colors.len())];
+            trees.push_str(&flower.paint("*").to_string());
+        } else {
+            trees.push(' ');
+        }
+    }
+    let grass: String = (0..width)
+        .map(|_| GRASS.paint([",", "'", "\""][rng.gen_range(0..3)]).to_string())
+        .collect();
+    vec![trees, grass]
+}
+
+/// An arched bridge over the water, with a pier every `BRIDGE_SPAN` columns.
+fn bridge_lines(width: usize, theme: &Theme, ascii: bool) -> Vec<String> {
+    let (deck, pier_top, pier, rise, fall) = if ascii { ('=', '+', '|', '/', '\\') } else { ('═', '╦', '║', '╱', '╲') };
+    // Distance from the last pier to the left.
+    let from_pier = |col: usize| (col + BRIDGE_SPAN / 2) % BRIDGE_SPAN;
+    let deck_line: String = (0..width)
+        .map(|col| if from_pier(col) == 0 { pier_top } else { deck })
+        .collect();
+    let mut lines = vec![theme.base_color.paint(&deck_line).to_string()];
+    // Each arch leaves the pier one column further out per row up.
+    for spring in [2, 1] {
+        let arches: String = (0..width)
+            .map(|col| match from_pier(col) {
+                0 => theme.base_color.paint(&pier.to_string()).to_string(),
+                d if d == spring => theme.roof_color.paint(&rise.to_string()).to_string(),
+                d if d == BRIDGE_SPAN - spring => theme.roof_color.paint(&fall.to_string()).to_string(),
+                _ if spring == 1 => theme.water_colors[col % theme.water_colors.len()].paint("~").to_string(),
+                _ => " ".to_string(),
+            })
+            .collect();
+        lines.push(arches);
+    }
+    lines.push(water_line(width, theme));
+    lines
+}
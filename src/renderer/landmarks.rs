@@ -0,0 +1,249 @@
+use crate::calendar::weekly_grid;
+use crate::renderer::building::CellSize;
+use crate::renderer::coloring::BuildingColors;
+
+/// Zero-contribution days before a comeback that earns a radio tower.
+pub(crate) const COMEBACK_GAP: usize = 7;
+
+/// Special buildings that mark notable days among the ones shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Landmark {
+    Lighthouse,
+    ClockTower,
+    RadioTower,
+    Stadium,
+}
+
+/// A landmark's art for one style and cell width: the top rows, a body
+/// pattern repeated down to the base, and the base row.
+pub(crate) struct LandmarkArt {
+    top: Vec<String>,
+    body: Vec<String>,
+    base: String,
+    width: usize,
+}
+
+impl Landmark {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Landmark::Lighthouse => "Lighthouse",
+            Landmark::ClockTower => "Clock tower",
+            Landmark::RadioTower => "Radio tower",
+            Landmark::Stadium => "Stadium",
+        }
+    }
+
+    pub(crate) fn meaning(&self) -> &'static str {
+        match self {
+            Landmark::Lighthouse => "busiest day",
+            Landmark::ClockTower => "end of the longest streak",
+            Landmark::RadioTower => "back after a quiet spell",
+            Landmark::Stadium => "busiest week",
+        }
+    }
+
+    /// The landmark drawn three columns wide, as it appears in the legend.
+    fn glyphs(&self, style: &str) -> (&'static [&'static str], &'static [&'static str], &'static str) {
+        match (self, style) {
+            (Landmark::Lighthouse, "blocks") => (&[" ▲ ", "▀█▀", "▐█▌"], &["███", "▒▒▒"], "███"),
+            (Landmark::Lighthouse, "ascii") => (&["\\|/", "-O-", "[_]"], &["|=|", "| |"], "/_\\"),
+            (Landmark::Lighthouse, "hash") => (&["\\#/", "-#-", "[#]"], &["###", "#=#"], "###"),
+            (Landmark::Lighthouse, _) => (&["⠑⢸⠊", "⠤⣿⠤", "⢸⣿⡇"], &["⣿⣿⣿", "⣀⣀⣀"], "⣸⣸⣸"),
+            (Landmark::ClockTower, "blocks") => (&[" ▲ ", "▐█▌", "(o)"], &["███", "█▐█"], "███"),
+            (Landmark::ClockTower, "ascii") => (&[" A ", "/_\\", "(o)"], &["| |", "|.|"], "|_|"),
+            (Landmark::ClockTower, "hash") => (&[" ^ ", "###", "(o)"], &["###", "# #"], "###"),
+            (Landmark::ClockTower, _) => (&[" ⡀ ", "⢠⣿⡄", "(o)"], &["⣿⣿⣿", "⣿⣶⣿"], "⣸⣸⣸"),
+            (Landmark::RadioTower, "blocks") => (&[")|(", " █ ", "▟█▙"], &["▐█▌"], "███"),
+            (Landmark::RadioTower, "ascii") => (&[")|(", " | ", "/|\\"], &["|X|", "|/|"], "/_\\"),
+            (Landmark::RadioTower, "hash") => (&[")#(", " # ", "###"], &["#X#"], "###"),
+            (Landmark::RadioTower, _) => (&[")⢸(", " ⢸ ", "⢀⣿⡀"], &["⢸⣿⡇", "⡜⣿⢣"], "⣸⣸⣸"),
+            (Landmark::Stadium, "blocks") => (&["╤ ╤", "█▄█"], &["▀█▀", "███"], "███"),
+            (Landmark::Stadium, "ascii") => (&["T T", "\\_/"], &["|=|"], "|_|"),
+            (Landmark::Stadium, "hash") => (&["# #", "#_#"], &["#=#"], "###"),
+            (Landmark::Stadium, _) => (&["⡇ ⢸", "⣷⣀⣾"], &["⣿⣤⣿", "⣿⣿⣿"], "⣸⣸⣸"),
+        }
+    }
+
+    /// The art fitted to `cell`: the top rows stay centered over the
+    /// building while the body and base stretch to its full width.
+    pub(crate) fn art(&self, style: &str, cell: CellSize) -> LandmarkArt {
+        let (top, body, base) = self.glyphs(style);
+        LandmarkArt {
+            top: top.iter().map(|glyphs| cell.center(glyphs)).collect(),
+            body: body.iter().map(|glyphs| cell.stretch(glyphs)).collect(),
+            base: cell.stretch(base),
+            width: cell.width,
+        }
+    }
+
+    /// A recognisable row of the landmark, for the legend.
+    pub(crate) fn swatch(&self, style: &str) -> &'static str {
+        let (top, _, base) = self.glyphs(style);
+        top.get(1).unwrap_or(&base)
+    }
+}
+
+/// Art family for a rendering style; ASCII-only blocks use the ASCII art.
+pub(crate) fn landmark_style(style: &str, ascii_only: bool) -> &str {
+    if ascii_only && style == "blocks" { "ascii" } else { style }
+}
+
+/// Picks at most one day for each landmark among the given days. When two
+/// landmarks want the same day the earlier one in `Landmark` order keeps it.
+pub(crate) fn place_landmarks(contributions: &[u32], dates: &[String]) -> Vec<Option<Landmark>> {
+    let mut placed: Vec<Option<Landmark>> = vec![None; contributions.len()];
+    let max = contributions.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return placed;
+    }
+
+    if let Some(day) = contributions.iter().rposition(|&count| count == max) {
+        placed[day] = Some(Landmark::Lighthouse);
+    }
+
+    // Longest run of active days; the most recent one wins ties.
+    let mut best: Option<(usize, usize)> = None;
+    let mut run = 0;
+    for (day, &count) in contributions.iter().enumerate() {
+        run = if count > 0 { run + 1 } else { 0 };
+        if run >= 2 && best.is_none_or(|(length, _)| run >= length) {
+            best = Some((run, day));
+        }
+    }
+    if let Some((_, end)) = best
+        && placed[end].is_none()
+    {
+        placed[end] = Some(Landmark::ClockTower);
+    }
+
+    let comeback = (COMEBACK_GAP..contributions.len())
+        .rev()
+        .find(|&day| contributions[day] > 0 && contributions[day - COMEBACK_GAP..day].iter().all(|&count| count == 0));
+    if let Some(day) = comeback
+        && placed[day].is_none()
+    {
+        placed[day] = Some(Landmark::RadioTower);
+    }
+
+    let days: Vec<usize> = (0..contributions.len()).collect();
+    let busiest_week = weekly_grid(&days, dates)
+        .into_iter()
+        .map(|week| week.into_iter().flatten().collect::<Vec<usize>>())
+        .max_by_key(|week| week.iter().map(|&day| contributions[day]).sum::<u32>());
+    if let Some(week) = busiest_week
+        && let Some(&day) = week
+            .iter()
+            .filter(|&&day| placed[day].is_none() && contributions[day] > 0)
+            .max_by_key(|&&day| contributions[day])
+    {
+        placed[day] = Some(Landmark::Stadium);
+    }
+
+    placed
+}
+
+/// One row of a landmark standing `height` rows tall, painted with the
+/// day's building colors and the theme's roof, base and moon colors. Short
+/// days are raised until the whole landmark fits; nothing stands before
+/// construction starts.
+pub(crate) fn get_special_building(
+    art: &LandmarkArt,
+    height: u32,
+    row: u32,
+    count: u32,
+    date: Option<&str>,
+    colors: &BuildingColors,
+) -> String {
+    if height == 0 {
+        return " ".repeat(art.width);
+    }
+    let height = height.max(art.top.len() as u32 + 2);
+    let theme = &colors.theme;
+    if row > height {
+        return " ".repeat(art.width);
+    }
+    if row == 1 {
+        return theme.base_color.paint(&art.base).to_string();
+    }
+    let from_top = (height - row) as usize;
+    match art.top.get(from_top) {
+        // The topmost row is the landmark's light, signal or floodlights.
+        Some(glyphs) if from_top == 0 => theme.moon_color.paint(glyphs).to_string(),
+        Some(glyphs) => theme.roof_color.paint(glyphs).to_string(),
+        None => {
+            let glyphs = &art.body[(from_top - art.top.len()) % art.body.len()];
+            colors.building(height, count, date).paint(glyphs).to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::strip_ansi_codes;
+
+    /// Consecutive dates from Sunday 2024-03-03.
+    fn dates(days: usize) -> Vec<String> {
+        (0..days).map(|day| format!("2024-03-{:02}", day + 3)).collect()
+    }
+
+    fn day_of(contributions: &[u32], landmark: Landmark) -> Option<usize> {
+        place_landmarks(contributions, &dates(contributions.len()))
+            .iter()
+            .position(|&placed| placed == Some(landmark))
+    }
+
+    #[test]
+    fn quiet_days_get_no_landmarks() {
+        assert!(place_landmarks(&[], &[]).is_empty());
+        assert_eq!(place_landmarks(&[0, 0, 0], &dates(3)), vec![None; 3]);
+    }
+
+    #[test]
+    fn lighthouse_marks_the_latest_busiest_day() {
+        assert_eq!(day_of(&[5, 0, 5, 1], Landmark::Lighthouse), Some(2));
+    }
+
+    #[test]
+    fn clock_tower_ends_the_latest_longest_streak() {
+        assert_eq!(day_of(&[1, 1, 0, 1, 1, 0, 9], Landmark::ClockTower), Some(4));
+        // The lighthouse keeps a day both want.
+        assert_eq!(day_of(&[1, 9, 0], Landmark::Lighthouse), Some(1));
+        assert_eq!(day_of(&[1, 9, 0], Landmark::ClockTower), None);
+    }
+
+    #[test]
+    fn radio_tower_marks_the_latest_comeback() {
+        let mut contributions = vec![3];
+        contributions.extend([0; COMEBACK_GAP]);
+        contributions.push(2);
+        contributions.extend([0; COMEBACK_GAP]);
+        contributions.push(1);
+        assert_eq!(day_of(&contributions, Landmark::RadioTower), Some(16));
+    }
+
+    #[test]
+    fn stadium_takes_the_busiest_free_day_of_the_busiest_week() {
+        let contributions = [0, 2, 0, 2, 0, 2, 0, 0, 1, 0, 3, 0, 4, 0];
+        assert_eq!(day_of(&contributions, Landmark::Lighthouse), Some(12));
+        assert_eq!(day_of(&contributions, Landmark::Stadium), Some(10));
+    }
+
+    #[test]
+    fn short_days_are_raised_to_fit_the_landmark() {
+        let colors = BuildingColors::new("synthwave", "height", &[1], 30);
+        let art = Landmark::Lighthouse.art("braille", CellSize::default());
+        let row = |height: u32, row: u32| strip_ansi_codes(&get_special_building(&art, height, row, 1, None, &colors));
+
+        // Three top rows plus a body row and the base.
+        assert_eq!(row(1, 1), "⣸⣸⣸");
+        assert_eq!(row(1, 2), "⣿⣿⣿");
+        assert_eq!(row(1, 5), "⠑⢸⠊");
+        assert_eq!(row(1, 6), "   ");
+        // Taller days keep their height.
+        assert_eq!(row(8, 8), "⠑⢸⠊");
+        assert_eq!(row(8, 9), "   ");
+        // Nothing stands before construction starts.
+        assert_eq!(row(0, 1), "   ");
+    }
+}
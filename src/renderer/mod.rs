@@ -1,3 +1,10 @@
+pub mod animation;
 pub mod building;
+pub mod coloring;
+pub mod font;
+pub mod ground;
+pub mod landmarks;
 pub mod skyline;
-pub mod sky_elements; 
\ No newline at end of file
+pub mod sky_elements;
+pub mod theme;
+pub mod timelapse; 
\ No newline at end of file
@@ -1,95 +1,533 @@
-use colored::*;
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use crate::calendar::{days_from_civil, month_of, parse_date};
+use crate::renderer::building::{contribution_level, level_thresholds, CellSize};
+use colored::Color;
+use crate::renderer::coloring::sample_ramp;
+use crate::renderer::theme::{get_theme, Paint, Theme};
 
+pub const SKY_MODES: [&str; 5] = ["detailed", "none", "day", "sunset", "weather"];
+pub const MOON_MODES: [&str; 2] = ["contributions", "astronomical"];
+
+const SYNODIC_MONTH: f64 = 29.530588853;
+// A known new moon, 2000-01-06 18:14 UTC, in days since 1970-01-01.
+const REFERENCE_NEW_MOON: f64 = 10_962.0 + (18.0 * 60.0 + 14.0) / 1440.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MoonType {
-    Crescent,
-    Quarter,
-    Gibbous,
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
     Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl MoonType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MoonType::New => "New Moon",
+            MoonType::WaxingCrescent => "Waxing Crescent",
+            MoonType::FirstQuarter => "First Quarter",
+            MoonType::WaxingGibbous => "Waxing Gibbous",
+            MoonType::Full => "Full Moon",
+            MoonType::WaningGibbous => "Waning Gibbous",
+            MoonType::LastQuarter => "Last Quarter",
+            MoonType::WaningCrescent => "Waning Crescent",
+        }
+    }
+
+    /// Lit fraction of the disc (0-1) and whether the lit side is on the right.
+    pub fn illumination(&self) -> (f32, bool) {
+        match self {
+            MoonType::New => (0.0, true),
+            MoonType::WaxingCrescent => (0.25, true),
+            MoonType::FirstQuarter => (0.5, true),
+            MoonType::WaxingGibbous => (0.75, true),
+            MoonType::Full => (1.0, true),
+            MoonType::WaningGibbous => (0.75, false),
+            MoonType::LastQuarter => (0.5, false),
+            MoonType::WaningCrescent => (0.25, false),
+        }
+    }
 }
 
 pub fn select_moon_type(total_contributions: u32) -> MoonType {
     match total_contributions {
-        0..=499 => MoonType::Crescent,
-        500..=1499 => MoonType::Quarter,
-        1500..=2999 => MoonType::Gibbous,
+        0..=499 => MoonType::WaxingCrescent,
+        500..=1499 => MoonType::FirstQuarter,
+        1500..=2999 => MoonType::WaxingGibbous,
         _ => MoonType::Full,
     }
 }
 
-pub fn print_night_sky(width: usize, moon_type: &MoonType) {
-    let mut rng = rand::thread_rng();
-        
-    let star_chars = ['*', '·', '+', '✦', '⋆', '✧'];
-    let twinkle_chars = ['.', '˚', '°', '*', '✦', ' '];
-    
-    let moon_lines = get_moon_art(moon_type);
-    let moon_start_col = if width > 80 { width - 30 } else if width > 60 { width - 25 } else { width - 20 };
-    let moon_start_row = 2;
-    let moon_end_row = moon_start_row + moon_lines.len();
-    
-    for row in 0..15 {
-        print!("{}", " ".repeat(5));
-        
-        for col in 0..width {
-            let mut cell_filled = false;
-            
-            if row >= moon_start_row && row < moon_end_row && col >= moon_start_col {
-                let moon_row_idx = row - moon_start_row;
-                let moon_col_idx = col - moon_start_col;
-                
-                if moon_row_idx < moon_lines.len() {
-                    let moon_line = &moon_lines[moon_row_idx];
-                    if moon_col_idx < moon_line.len() {
-                        let moon_char = moon_line.chars().nth(moon_col_idx).unwrap_or(' ');
-                        if moon_char != ' ' {
-                            print!("{}", format!("{}", moon_char).bright_yellow().bold());
-                            cell_filled = true;
-                        }
-                    }
-                }
+/// The real lunar phase at noon UTC on a `YYYY-MM-DD` date, counted in
+/// synodic months from a known new moon.
+pub fn lunar_phase(date: &str) -> Option<MoonType> {
+    let (year, month, day) = parse_date(date)?;
+    let days = days_from_civil(year, month, day) as f64 + 0.5;
+    let age = (days - REFERENCE_NEW_MOON).rem_euclid(SYNODIC_MONTH) / SYNODIC_MONTH;
+    let phases = [
+        MoonType::New,
+        MoonType::WaxingCrescent,
+        MoonType::FirstQuarter,
+        MoonType::WaxingGibbous,
+        MoonType::Full,
+        MoonType::WaningGibbous,
+        MoonType::LastQuarter,
+        MoonType::WaningCrescent,
+    ];
+    Some(phases[(age * 8.0).round() as usize % 8])
+}
+
+/// Moon for `--moon`: the phase on the last shown date in `astronomical`
+/// mode, otherwise the shape earned by the contribution total.
+pub fn moon_for(mode: &str, total_contributions: u32, dates: &[String]) -> MoonType {
+    if mode.eq_ignore_ascii_case("astronomical")
+        && let Some(phase) = dates.last().and_then(|date| lunar_phase(date))
+    {
+        return phase;
+    }
+    select_moon_type(total_contributions)
+}
+
+const SKY_ROWS: usize = 15;
+/// Zero-contribution runs at least this long get a cloud.
+pub(crate) const CLOUD_GAP: usize = 5;
+/// How far back `--sky weather` looks for inactivity.
+pub(crate) const RECENT_DAYS: usize = 14;
+
+type SkyCell = Option<(char, Paint)>;
+
+pub(crate) const SUN: Paint = Paint { color: Color::BrightYellow, bold: true };
+const CLOUD: Paint = Paint { color: Color::BrightWhite, bold: false };
+
+const SUN_ART: [&str; 7] = [
+    "    \\   |   /",
+    "      .---.",
+    "  -- /     \\ --",
+    "     |     |",
+    "  -- \\     / --",
+    "      `---'",
+    "    /   |   \\",
+];
+
+const SETTING_SUN_ART: [&str; 3] = [
+    "      _.-'''''-._",
+    "    .'           '.",
+    "   /               \\",
+];
+
+/// One shown day as the sky sees it.
+#[derive(Clone, Copy, Debug)]
+pub struct SkyDay {
+    pub count: u32,
+    /// GitHub contribution level, 0-4, against the whole year.
+    pub level: usize,
+    /// More contributions than any earlier day.
+    pub record: bool,
+}
+
+/// What the sky knows about the city below it: one entry per shown day,
+/// `pitch` columns apart, so stars and clouds land above their building.
+pub struct SkyLayout {
+    pub width: usize,
+    pub indent: usize,
+    pub pitch: usize,
+    /// Columns of each building within its `pitch`.
+    pub cell_width: usize,
+    pub ascii: bool,
+    pub days: Vec<SkyDay>,
+    /// Days without contributions among the last `RECENT_DAYS`, for weather.
+    pub dry_days: usize,
+    /// Month of the last day, which decides between rain and snow.
+    pub month: Option<u32>,
+}
+
+impl SkyLayout {
+    /// Sky above the last `shown` days of `contributions`, with buildings
+    /// laid out in `cell`s.
+    pub fn new(contributions: &[u32], dates: &[String], shown: usize, cell: CellSize, indent: usize, ascii: bool) -> Self {
+        let shown = shown.min(contributions.len());
+        let thresholds = level_thresholds(contributions);
+        let start = contributions.len() - shown;
+        let mut best = 0;
+        let mut days = Vec::with_capacity(shown);
+        for (i, &count) in contributions.iter().enumerate() {
+            if i >= start {
+                days.push(SkyDay {
+                    count,
+                    level: contribution_level(count, &thresholds),
+                    record: best > 0 && count > best,
+                });
             }
-            
-            if !cell_filled {
-                let star_probability = match row {
-                    0..=4 => 0.08,  
-                    5..=9 => 0.05,  
-                    10..=14 => 0.03, 
-                    _ => 0.0,
+            best = best.max(count);
+        }
+        SkyLayout {
+            days,
+            pitch: cell.pitch(),
+            cell_width: cell.width,
+            ..SkyLayout::unaligned(contributions, dates, cell.columns(shown), indent, ascii)
+        }
+    }
+
+    /// A sky for views that are not one building per day: it still knows the
+    /// recent weather but places nothing per day, so night falls back to a
+    /// random star field.
+    pub fn unaligned(contributions: &[u32], dates: &[String], width: usize, indent: usize, ascii: bool) -> Self {
+        let recent = &contributions[contributions.len().saturating_sub(RECENT_DAYS)..];
+        SkyLayout {
+            width,
+            indent,
+            pitch: 1,
+            cell_width: 1,
+            ascii,
+            days: Vec::new(),
+            dry_days: recent.iter().filter(|&&count| count == 0).count(),
+            month: dates.last().and_then(|date| month_of(date)),
+        }
+    }
+
+    pub fn records(&self) -> usize {
+        self.days.iter().filter(|day| day.record).count()
+    }
+
+    /// Streaks of two or more active days, each drawn as a constellation.
+    pub fn constellations(&self) -> usize {
+        self.runs(|day| day.count > 0).filter(|&run| run >= 2).count()
+    }
+
+    pub fn clouds(&self) -> usize {
+        self.runs(|day| day.count == 0).filter(|&run| run >= CLOUD_GAP).count()
+    }
+
+    /// Lengths of the runs of consecutive days matching `matches`.
+    fn runs(&self, matches: impl Fn(&SkyDay) -> bool) -> impl Iterator<Item = usize> {
+        let lengths: Vec<usize> = self.days.split(|day| !matches(day)).map(|run| run.len()).collect();
+        lengths.into_iter().filter(|&len| len > 0)
+    }
+
+    /// Center column of a day's building.
+    fn column(&self, day: usize) -> usize {
+        day * self.pitch + self.cell_width / 2
+    }
+}
+
+pub fn print_sky(mode: &str, layout: &SkyLayout, moon_type: &MoonType, theme: &str, seed: u64) {
+    for line in sky_lines(mode, layout, Some(moon_type), theme, seed) {
+        println!("{}", line);
+    }
+}
+
+/// Paints the sky for `--sky`; `detailed` is the night sky.
+pub fn sky_lines(mode: &str, layout: &SkyLayout, moon_type: Option<&MoonType>, theme: &str, seed: u64) -> Vec<String> {
+    match mode.to_lowercase().as_str() {
+        "none" => Vec::new(),
+        "day" => day_sky_lines(layout, seed),
+        "sunset" => sunset_sky_lines(layout, theme, seed),
+        "weather" => weather_sky_lines(layout, theme, seed),
+        _ => night_sky_lines(layout, moon_type, theme, seed),
+    }
+}
+
+/// Draws the sky: a star over every active day, brighter and higher for
+/// busier days, streaks linked into constellations, shooting stars over
+/// personal records and clouds over long quiet spells.
+fn night_sky_lines(layout: &SkyLayout, moon_type: Option<&MoonType>, theme: &str, seed: u64) -> Vec<String> {
+    let theme = get_theme(theme);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut grid = sky_grid(layout);
+    let star_paint = |level: usize| theme.star_colors[level.saturating_sub(1) % theme.star_colors.len()];
+
+    draw_clouds(&mut grid, layout, theme.sky_color, &mut rng);
+
+    let (flat, rising, falling) = if layout.ascii { ('-', '/', '\\') } else { ('─', '╱', '╲') };
+    let star_glyphs = if layout.ascii { ['.', '+', '*', '*'] } else { ['·', '+', '✦', '★'] };
+    let mut previous: Option<(usize, usize)> = None;
+    for (i, day) in layout.days.iter().enumerate() {
+        if day.count == 0 {
+            previous = None;
+            continue;
+        }
+        // Busier days sit higher; streaks drift one row at a time so the
+        // constellation lines stay readable.
+        let target = 9 - 2 * (day.level.clamp(1, 4) - 1);
+        let row = match previous {
+            Some((prev_row, _)) => (prev_row as i32 + (target as i32 - prev_row as i32).signum()) as usize,
+            None => target - rng.gen_range(0..=1),
+        };
+        let col = layout.column(i);
+        if let Some((prev_row, prev_col)) = previous {
+            let mut last_row = prev_row;
+            for x in prev_col + 1..col {
+                let t = (x - prev_col) as f32 / (col - prev_col) as f32;
+                let y = (prev_row as f32 + (row as f32 - prev_row as f32) * t).round() as usize;
+                let glyph = match y.cmp(&last_row) {
+                    std::cmp::Ordering::Less => rising,
+                    std::cmp::Ordering::Greater => falling,
+                    std::cmp::Ordering::Equal => flat,
                 };
-                
-                if rng.r#gen::<f64>() < star_probability {
-                    let use_twinkle = rng.r#gen::<f64>() < 0.3;
-                    let star_char = if use_twinkle {
-                        *twinkle_chars.choose(&mut rng).unwrap()
-                    } else {
-                        *star_chars.choose(&mut rng).unwrap()
-                    };
-                    
-                    let colored_star = match rng.gen_range(0..6) {
-                        0 => format!("{}", star_char).bright_white().to_string(),
-                        1 => format!("{}", star_char).bright_cyan().to_string(),
-                        2 => format!("{}", star_char).bright_blue().to_string(),
-                        3 => format!("{}", star_char).cyan().to_string(),
-                        4 => format!("{}", star_char).white().to_string(),
-                        _ => format!("{}", star_char).bright_yellow().to_string(),
-                    };
-                    print!("{}", colored_star);
-                } else {
-                    print!(" ");
+                set_cell(&mut grid, y, x, glyph, theme.sky_color);
+                last_row = y;
+            }
+        }
+        set_cell(&mut grid, row, col, star_glyphs[day.level.clamp(1, 4) - 1], star_paint(day.level));
+        previous = Some((row, col));
+    }
+
+    let records: Vec<usize> = layout.days.iter().enumerate().filter(|(_, day)| day.record).map(|(i, _)| layout.column(i)).collect();
+    for col in records {
+        let head = if layout.ascii { '*' } else { '✦' };
+        set_cell(&mut grid, 1, col, head, star_paint(4));
+        if col >= 3 {
+            set_cell(&mut grid, 1, col - 1, falling, theme.sky_color);
+            set_cell(&mut grid, 0, col - 2, flat, theme.sky_color);
+            set_cell(&mut grid, 0, col - 3, if layout.ascii { '.' } else { '·' }, theme.sky_color);
+        }
+    }
+
+    if let Some(moon_type) = moon_type {
+        draw_art(&mut grid, &get_moon_art(moon_type), 2, celestial_column(layout.width), theme.moon_color, false);
+    }
+
+    // Faint background twinkles; without per-day data they are the whole sky.
+    let star_chars = ['*', '·', '+', '✦', '⋆', '✧'];
+    let twinkle_chars = if layout.ascii { ['.', '\'', '`'] } else { ['.', '˚', '°'] };
+    for (row, cells) in grid.iter_mut().enumerate() {
+        let star_probability = match (layout.days.is_empty(), row) {
+            (false, _) => 0.012,
+            (true, 0..=4) => 0.08,
+            (true, 5..=9) => 0.05,
+            (true, _) => 0.03,
+        };
+        for cell in cells.iter_mut().filter(|cell| cell.is_none()) {
+            if rng.r#gen::<f64>() >= star_probability {
+                continue;
+            }
+            *cell = if layout.days.is_empty() && !layout.ascii && rng.r#gen::<f64>() >= 0.3 {
+                Some((*star_chars.choose(&mut rng).unwrap(), *theme.star_colors.choose(&mut rng).unwrap()))
+            } else {
+                Some((*twinkle_chars.choose(&mut rng).unwrap(), theme.sky_color))
+            };
+        }
+    }
+
+    grid_lines(&grid, layout.indent)
+}
+
+/// Bright daytime sky: a sun where the moon would be and a few clouds, plus
+/// one over every quiet spell. Buildings switch their window lights off.
+fn day_sky_lines(layout: &SkyLayout, seed: u64) -> Vec<String> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut grid = sky_grid(layout);
+    draw_clouds(&mut grid, layout, CLOUD, &mut rng);
+    // Fair-weather clouds stay clear of the sun.
+    let sun_column = celestial_column(layout.width);
+    for _ in 0..layout.width / 40 + 1 {
+        let width = rng.gen_range(8..=16);
+        if width < sun_column {
+            let left = rng.gen_range(0..=sun_column - width);
+            draw_cloud(&mut grid, rng.gen_range(1..=7), left, width, CLOUD);
+        }
+    }
+    draw_art(&mut grid, &SUN_ART, 1, sun_column, SUN, true);
+    grid_lines(&grid, layout.indent)
+}
+
+/// Streaky bands that fade from the theme's sky color at the top to its
+/// brightest building color and moon glow at the horizon, over a setting sun.
+fn sunset_sky_lines(layout: &SkyLayout, theme: &str, seed: u64) -> Vec<String> {
+    let theme = get_theme(theme);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut grid = sky_grid(layout);
+    let ramp = sunset_ramp(&theme);
+    let band = if layout.ascii { '-' } else { '─' };
+    for (row, cells) in grid.iter_mut().enumerate() {
+        let depth = row as f32 / (SKY_ROWS - 1) as f32;
+        let paint = sample_ramp(&ramp, depth);
+        let density = 0.08 + 0.8 * depth * depth;
+        for cell in cells.iter_mut() {
+            if rng.r#gen::<f32>() < density {
+                *cell = Some((band, paint));
+            }
+        }
+    }
+    let top = SKY_ROWS - SETTING_SUN_ART.len();
+    draw_art(&mut grid, &SETTING_SUN_ART, top, celestial_column(layout.width), theme.moon_color, true);
+    grid_lines(&grid, layout.indent)
+}
+
+/// Overcast sky whose rain, or snow in winter, gets heavier the more of the
+/// last `RECENT_DAYS` went without contributions.
+fn weather_sky_lines(layout: &SkyLayout, theme: &str, seed: u64) -> Vec<String> {
+    let theme = get_theme(theme);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut grid = sky_grid(layout);
+    let (_, glyphs, paint) = precipitation(layout, &theme);
+    let dryness = layout.dry_days as f64 / RECENT_DAYS as f64;
+    let density = 0.02 + 0.3 * dryness;
+    for row in 3..SKY_ROWS {
+        for col in 0..layout.width {
+            if rng.r#gen::<f64>() < density {
+                set_cell(&mut grid, row, col, *glyphs.choose(&mut rng).unwrap(), paint);
+            }
+        }
+    }
+    draw_clouds(&mut grid, layout, theme.sky_color, &mut rng);
+    // The cloud deck thickens with the weather.
+    let mut left = 0;
+    while left + 10 <= layout.width {
+        let width = rng.gen_range(10..=18).min(layout.width - left);
+        if rng.r#gen::<f64>() < 0.35 + 0.65 * dryness {
+            draw_cloud(&mut grid, rng.gen_range(0..=1), left, width, CLOUD);
+        }
+        left += width - 2;
+    }
+    grid_lines(&grid, layout.indent)
+}
+
+/// Top-to-horizon colors for `--sky sunset`.
+pub(crate) fn sunset_ramp(theme: &Theme) -> Vec<Paint> {
+    let buildings = &theme.building_colors;
+    vec![theme.sky_color, buildings[buildings.len() / 2], buildings[buildings.len() - 1], theme.moon_color]
+}
+
+/// Name, glyphs and color of the precipitation for `--sky weather`.
+pub(crate) fn precipitation(layout: &SkyLayout, theme: &Theme) -> (&'static str, [char; 3], Paint) {
+    let snow = matches!(layout.month, Some(12 | 1 | 2));
+    match (snow, layout.ascii) {
+        (true, false) => ("Snow", ['*', '❄', '·'], CLOUD),
+        (true, true) => ("Snow", ['*', '.', '+'], CLOUD),
+        (false, false) => ("Rain", ['╱', '/', '\''], theme.water_colors[0]),
+        (false, true) => ("Rain", ['/', '/', '\''], theme.water_colors[0]),
+    }
+}
+
+fn sky_grid(layout: &SkyLayout) -> Vec<Vec<SkyCell>> {
+    vec![vec![None; layout.width]; SKY_ROWS]
+}
+
+fn grid_lines(grid: &[Vec<SkyCell>], indent: usize) -> Vec<String> {
+    grid.iter()
+        .map(|cells| {
+            let mut line = " ".repeat(indent);
+            for cell in cells {
+                match cell {
+                    Some((ch, paint)) if *ch != ' ' => line.push_str(&paint.paint(&ch.to_string()).to_string()),
+                    _ => line.push(' '),
                 }
             }
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Left edge of the moon or sun, near the right end of the sky.
+fn celestial_column(width: usize) -> usize {
+    if width > 80 { width - 30 } else if width > 60 { width - 25 } else { width.saturating_sub(20) }
+}
+
+/// Copies art into the sky. Solid art also blanks the spaces inside each
+/// line, so nothing behind it shows through.
+fn draw_art<S: AsRef<str>>(grid: &mut [Vec<SkyCell>], art: &[S], top: usize, left: usize, paint: Paint, solid: bool) {
+    for (row, line) in art.iter().enumerate() {
+        let line = line.as_ref();
+        let start = line.len() - line.trim_start().len();
+        let end = line.trim_end().chars().count();
+        for (offset, ch) in line.chars().enumerate() {
+            if ch != ' ' || (solid && offset >= start && offset < end) {
+                set_cell(grid, top + row, left + offset, ch, paint);
+            }
         }
-        
-        println!();
     }
 }
 
+/// Floats a cloud somewhere over every run of `CLOUD_GAP` or more empty days.
+fn draw_clouds(grid: &mut [Vec<SkyCell>], layout: &SkyLayout, paint: Paint, rng: &mut ChaCha8Rng) {
+    let mut day = 0;
+    while day < layout.days.len() {
+        let run = layout.days[day..].iter().take_while(|d| d.count == 0).count();
+        if run >= CLOUD_GAP {
+            let span = (run - 1) * layout.pitch + layout.cell_width;
+            let width = span.min(22);
+            let left = day * layout.pitch + rng.gen_range(0..=span - width);
+            draw_cloud(grid, rng.gen_range(10..=SKY_ROWS - 3), left, width, paint);
+        }
+        day += run.max(1);
+    }
+}
+
+/// A three-row cloud `width` (at least 6) columns wide.
+fn draw_cloud(grid: &mut [Vec<SkyCell>], row: usize, left: usize, width: usize, paint: Paint) {
+    let edge = "-".repeat(width - 4);
+    let shape = [format!(" .{}. ", edge), format!("({})", " ".repeat(width - 2)), format!(" `{}' ", edge)];
+    draw_art(grid, &shape, row, left, paint, true);
+}
+
+fn set_cell(grid: &mut [Vec<SkyCell>], row: usize, col: usize, ch: char, paint: Paint) {
+    if let Some(cell) = grid.get_mut(row).and_then(|cells| cells.get_mut(col)) {
+        *cell = Some((ch, paint));
+    }
+}
+
+fn get_moon_art(moon_type: &MoonType) -> Vec<String> {
+    let art: Vec<String> = moon_art(moon_type).iter().map(|line| line.to_string()).collect();
+    // The drawings are lit from the left, like a waning moon; flip them when
+    // `illumination` puts the light on the right.
+    match moon_type.illumination() {
+        (lit, true) if lit > 0.0 && lit < 1.0 => mirror(&art),
+        _ => art,
+    }
+}
 
+/// Flips moon art left to right, so a waning shape becomes its waxing twin.
+fn mirror(lines: &[String]) -> Vec<String> {
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| {
+            let padded = format!("{:<width$}", line, width = width);
+            let flipped: String = padded
+                .chars()
+                .rev()
+                .map(|ch| match ch {
+                    '(' => ')',
+                    ')' => '(',
+                    '/' => '\\',
+                    '\\' => '/',
+                    '<' => '>',
+                    '>' => '<',
+                    '[' => ']',
+                    ']' => '[',
+                    '`' => '\'',
+                    '\'' => '`',
+                    other => other,
+                })
+                .collect();
+            flipped.trim_end().to_string()
+        })
+        .collect()
+}
 
-fn get_moon_art(moon_type: &MoonType) -> Vec<&'static str> {
+/// Moon drawings, each lit from the left.
+fn moon_art(moon_type: &MoonType) -> Vec<&'static str> {
     match moon_type {
-        MoonType::Crescent => vec![
+        MoonType::New => vec![
+            "         .  -  ~  -  .",
+            "     .                   .",
+            "   .                       .",
+            "  .                         .",
+            " .                           .",
+            " :                           :",
+            " .                           .",
+            "  .                         .",
+            "   .                       .",
+            "     .                   .",
+            "         '  -  ~  -  '",
+        ],
+        MoonType::WaxingCrescent | MoonType::WaningCrescent => vec![
             "               ____....",
             "           a#####~:::::::",
             "       a######P\";:::::::::::",
@@ -109,7 +547,7 @@ fn get_moon_art(moon_type: &MoonType) -> Vec<&'static str> {
             "       `d######a.:::::::::",
             "          `~9#####.::::",
         ],
-        MoonType::Quarter => vec![
+        MoonType::FirstQuarter | MoonType::LastQuarter => vec![
             "          ______          ",
             "      .-'` .    `'-.    ",
             "    .'  '    .---.  '.  ",
@@ -127,7 +565,7 @@ fn get_moon_art(moon_type: &MoonType) -> Vec<&'static str> {
             "    '.  '  . `'---'`.'",
             "      `'-..._____.-`",
         ],
-        MoonType::Gibbous => vec![
+        MoonType::WaxingGibbous | MoonType::WaningGibbous => vec![
             "o                     __...__",
             "              *   .--'    __.=-.",
             "     |          ./     .-'",
@@ -144,7 +582,8 @@ fn get_moon_art(moon_type: &MoonType) -> Vec<&'static str> {
             "    .             `--._    `--'",
             "                       `---~~`",
         ],
-        MoonType::Full => vec![
+        // Full.
+        _ => vec![
             "         ___---___",
             "      .--         --.",
             "    ./   ()      .-. \\.",
@@ -162,4 +601,57 @@ fn get_moon_art(moon_type: &MoonType) -> Vec<&'static str> {
             "            ---",
         ],
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_on_known_dates() {
+        assert_eq!(lunar_phase("2024-01-11"), Some(MoonType::New));
+        assert_eq!(lunar_phase("2024-01-18"), Some(MoonType::FirstQuarter));
+        assert_eq!(lunar_phase("2024-01-25"), Some(MoonType::Full));
+        assert_eq!(lunar_phase("2024-02-02"), Some(MoonType::LastQuarter));
+        assert_eq!(lunar_phase("2024-02-09"), Some(MoonType::New));
+        assert_eq!(lunar_phase("not a date"), None);
+    }
+
+    #[test]
+    fn phases_turn_from_waxing_to_waning_at_full_and_new_moon() {
+        assert_eq!(lunar_phase("2024-01-23"), Some(MoonType::WaxingGibbous));
+        assert_eq!(lunar_phase("2024-01-24"), Some(MoonType::Full));
+        assert_eq!(lunar_phase("2024-01-27"), Some(MoonType::Full));
+        assert_eq!(lunar_phase("2024-01-28"), Some(MoonType::WaningGibbous));
+        assert_eq!(lunar_phase("2024-02-07"), Some(MoonType::WaningCrescent));
+        assert_eq!(lunar_phase("2024-02-08"), Some(MoonType::New));
+        assert_eq!(lunar_phase("2024-02-11"), Some(MoonType::New));
+        assert_eq!(lunar_phase("2024-02-12"), Some(MoonType::WaxingCrescent));
+    }
+
+    #[test]
+    fn crescent_art_is_lit_where_illumination_says() {
+        // `#` is the lit surface of the crescent drawing.
+        for phase in [MoonType::WaxingCrescent, MoonType::WaningCrescent] {
+            let art = get_moon_art(&phase);
+            let width = art.iter().map(|line| line.chars().count()).max().unwrap() as f32;
+            let lit: Vec<f32> = art
+                .iter()
+                .flat_map(|line| line.chars().enumerate().filter(|&(_, ch)| ch == '#').map(|(col, _)| col as f32))
+                .collect();
+            let centre = lit.iter().sum::<f32>() / lit.len() as f32;
+            assert_eq!(centre > width / 2.0, phase.illumination().1, "{}", phase.name());
+        }
+    }
+
+    #[test]
+    fn waxing_and_waning_art_are_mirror_images() {
+        for (waxing, waning) in [
+            (MoonType::WaxingCrescent, MoonType::WaningCrescent),
+            (MoonType::FirstQuarter, MoonType::LastQuarter),
+            (MoonType::WaxingGibbous, MoonType::WaningGibbous),
+        ] {
+            assert_ne!(get_moon_art(&waxing), get_moon_art(&waning));
+            assert_eq!(get_moon_art(&waxing), mirror(&get_moon_art(&waning)));
+        }
+    }
+}
@@ -1,338 +1,323 @@
 use colored::*;
-use crate::renderer::building::{get_max_height, compute_building_heights};
-use crate::renderer::sky_elements::{select_moon_type, print_night_sky};
-use rand::seq::SliceRandom;
-
-#[derive(Clone)]
-struct Theme {
-    building_colors: Vec<fn(&str) -> ColoredString>,
-    window_colors: Vec<fn(&str) -> ColoredString>,
-    antenna_color: fn(&str) -> ColoredString,
-    roof_color: fn(&str) -> ColoredString,
-    base_color: fn(&str) -> ColoredString,
-}
-
-fn get_theme(theme: &str) -> Theme {
-    let theme_name = theme.to_lowercase();
-    if theme_name == "random" {
-        let themes = ["synthwave", "dracula", "solarized", "cyberpunk", "matrix", "sunset"];
-        let mut rng = rand::thread_rng();
-        let selected = themes.choose(&mut rng).unwrap();
-        return get_theme(selected);
+use crate::renderer::building::{get_max_height, level_thresholds, contribution_level, CellSize, HeightScale, Scale, ScaleReference};
+use crate::calendar::{weekly_grid, weekday, month_of, month_abbrev};
+use crate::renderer::sky_elements::{moon_for, precipitation, print_sky, sunset_ramp, SkyLayout, CLOUD_GAP, RECENT_DAYS, SUN};
+use crate::renderer::theme::{get_theme, pick_random_theme, Paint};
+use crate::renderer::coloring::BuildingColors;
+use crate::renderer::landmarks::{get_special_building, landmark_style, place_landmarks, LandmarkArt};
+use crate::renderer::ground::{ground_lines, ground_swatch};
+use crate::statistics::{rolling_average, Insights, Run, Statistics};
+
+/// Days drawn when `--width` is not given.
+pub const DEFAULT_WIDTH: usize = 25;
+
+/// Everything that controls how a skyline looks, independent of the data.
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    pub theme: String,
+    pub style: String,
+    pub scale: Scale,
+    pub ascii_only: bool,
+    pub sky_mode: String,
+    pub width: Option<usize>,
+    pub color_by: String,
+    /// `contributions` (shape earned by the total) or `astronomical`.
+    pub moon: String,
+    /// Seeds every random choice (stars, twinkles, `random` theme). The
+    /// generator is ChaCha8, whose stream is fixed across `rand` releases.
+    pub seed: u64,
+    /// Foreground under the buildings; `None` uses the theme's.
+    pub ground: Option<String>,
+    /// Rows the tallest building reaches.
+    pub height: u32,
+    pub cell: CellSize,
+    /// Shared scale from `--max` or `--scale-ref`; `None` scales each
+    /// render against its own busiest day.
+    pub reference: Option<ScaleReference>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            theme: "synthwave".to_string(),
+            style: "braille".to_string(),
+            scale: Scale::Dramatic,
+            ascii_only: false,
+            sky_mode: "detailed".to_string(),
+            width: None,
+            color_by: "height".to_string(),
+            moon: "contributions".to_string(),
+            seed: 0,
+            ground: None,
+            height: 30,
+            cell: CellSize::default(),
+            reference: None,
+        }
     }
-    
-    match theme_name.as_str() {
-        "dracula" => Theme {
-            building_colors: vec![
-                |s| s.bright_black().bold(),
-                |s| s.bright_blue().bold(),
-                |s| s.bright_magenta().bold(),
-                |s| s.bright_cyan().bold(),
-                |s| s.bright_yellow().bold(),
-                |s| s.bright_red().bold(),
-            ],
-            window_colors: vec![
-                |s| s.yellow(),
-                |s| s.bright_yellow(),
-                |s| s.bright_white(),
-                |s| s.bright_magenta(),
-                |s| s.bright_cyan(),
-                |s| s.bright_red(),
-            ],
-            antenna_color: |s| s.bright_red().bold(),
-            roof_color: |s| s.bright_white().bold(),
-            base_color: |s| s.bright_black().bold(),
-        },
-        "solarized" => Theme {
-            building_colors: vec![
-                |s| s.bright_yellow().bold(),
-                |s| s.yellow().bold(),
-                |s| s.bright_green().bold(),
-                |s| s.green().bold(),
-                |s| s.bright_blue().bold(),
-                |s| s.blue().bold(),
-            ],
-            window_colors: vec![
-                |s| s.bright_white(),
-                |s| s.bright_yellow(),
-                |s| s.bright_green(),
-                |s| s.bright_blue(),
-                |s| s.bright_cyan(),
-                |s| s.bright_magenta(),
-            ],
-            antenna_color: |s| s.bright_yellow().bold(),
-            roof_color: |s| s.bright_white().bold(),
-            base_color: |s| s.bright_black().bold(),
-        },
-        "cyberpunk" => Theme {
-            building_colors: vec![
-                |s| s.bright_magenta().bold(),
-                |s| s.magenta().bold(),
-                |s| s.bright_cyan().bold(),
-                |s| s.cyan().bold(),
-                |s| s.bright_yellow().bold(),
-                |s| s.yellow().bold(),
-            ],
-            window_colors: vec![
-                |s| s.bright_white(),
-                |s| s.bright_cyan(),
-                |s| s.bright_magenta(),
-                |s| s.bright_yellow(),
-                |s| s.white(),
-                |s| s.cyan(),
-            ],
-            antenna_color: |s| s.bright_magenta().bold(),
-            roof_color: |s| s.bright_cyan().bold(),
-            base_color: |s| s.magenta().bold(),
-        },
-        "matrix" => Theme {
-            building_colors: vec![
-                |s| s.green().bold(),
-                |s| s.bright_green().bold(),
-                |s| s.green(),
-                |s| s.bright_green(),
-                |s| s.bright_white().bold(),
-                |s| s.white().bold(),
-            ],
-            window_colors: vec![
-                |s| s.bright_green(),
-                |s| s.green(),
-                |s| s.bright_white(),
-                |s| s.white(),
-                |s| s.bright_green(),
-                |s| s.green(),
-            ],
-            antenna_color: |s| s.bright_green().bold(),
-            roof_color: |s| s.bright_white().bold(),
-            base_color: |s| s.green().bold(),
-        },
-        "sunset" => Theme {
-            building_colors: vec![
-                |s| s.bright_red().bold(),
-                |s| s.red().bold(),
-                |s| s.bright_yellow().bold(),
-                |s| s.yellow().bold(),
-                |s| s.bright_magenta().bold(),
-                |s| s.magenta().bold(),
-            ],
-            window_colors: vec![
-                |s| s.bright_yellow(),
-                |s| s.yellow(),
-                |s| s.bright_white(),
-                |s| s.bright_red(),
-                |s| s.bright_magenta(),
-                |s| s.red(),
-            ],
-            antenna_color: |s| s.bright_red().bold(),
-            roof_color: |s| s.bright_yellow().bold(),
-            base_color: |s| s.red().bold(),
-        },
-        _ => Theme { 
-            building_colors: vec![
-                |s| s.cyan().bold(),
-                |s| s.bright_cyan().bold(),
-                |s| s.blue().bold(),
-                |s| s.bright_blue().bold(),
-                |s| s.magenta().bold(),
-                |s| s.bright_magenta().bold(),
-            ],
-            window_colors: vec![
-                |s| s.cyan(),
-                |s| s.bright_cyan(),
-                |s| s.blue(),
-                |s| s.bright_blue(),
-                |s| s.magenta(),
-                |s| s.bright_magenta(),
-            ],
-            antenna_color: |s| s.bright_magenta().bold(),
-            roof_color: |s| s.bright_white().bold(),
-            base_color: |s| s.bright_black().bold(),
-        },
+}
+
+impl RenderOptions {
+    /// Fixes the seed and settles `--theme random` on a concrete theme, so
+    /// the header, legend and sky all agree and reruns are identical.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        if self.theme.eq_ignore_ascii_case("random") {
+            self.theme = pick_random_theme(seed).name;
+        }
+        self
+    }
+
+    /// Heights for `contributions`, against the shared reference if any.
+    pub(crate) fn height_scale(&self, contributions: &[u32], target_height: u32) -> HeightScale {
+        let reference = self.reference.as_ref().map_or(contributions, |r| &r.contributions);
+        HeightScale::new(reference, target_height, self.scale)
+    }
+
+    /// How many of the `available` most recent days get a building:
+    /// `--width`, else `DEFAULT_WIDTH`.
+    pub fn shown_days(&self, available: usize) -> usize {
+        available.min(self.width.unwrap_or(DEFAULT_WIDTH))
+    }
+
+    /// How many of the `available` days the chosen style draws: the
+    /// isometric city and the heatmap show them all, columns `shown_days`.
+    pub fn rendered_days(&self, available: usize) -> usize {
+        match self.style.to_lowercase().as_str() {
+            "iso" | "heatmap" => available,
+            _ => self.shown_days(available),
+        }
+    }
+
+    /// The foreground actually drawn: `--ground`, else the theme's own.
+    pub fn ground(&self) -> String {
+        self.ground.clone().unwrap_or_else(|| get_theme(&self.theme).ground)
     }
 }
 
+/// Default `--seed`: an FNV-1a hash of the username and the date range, so the
+/// same user and period always render the same sky.
+pub fn default_seed(username: &str, dates: &[String]) -> u64 {
+    let first = dates.first().map(String::as_str).unwrap_or_default();
+    let last = dates.last().map(String::as_str).unwrap_or_default();
+    format!("{}:{}:{}", username.to_lowercase(), first, last)
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
 #[allow(dead_code)]
-pub fn render_skyline(contributions: &[u32], theme: &str) {
+pub fn render_skyline(contributions: &[u32], dates: &[String], theme: &str) {
    
-    render_skyline_with_options(
-        contributions,
-        theme,
-        "braille",
-        "dramatic",
-        false,
-        "detailed",
-        None,
-    );
-}
-
-pub fn render_skyline_with_options(
-    contributions: &[u32],
-    theme: &str,
-    style: &str,
-    scale: &str,
-    ascii_only: bool,
-    sky_mode: &str,
-    width_opt: Option<usize>,
-) {
+    let options = RenderOptions { theme: theme.to_string(), ..RenderOptions::default() };
+    render_skyline_with_options(contributions, dates, &options);
+}
+
+pub fn render_skyline_with_options(contributions: &[u32], dates: &[String], options: &RenderOptions) {
     if contributions.is_empty() {
         println!("{}", "❌ No contribution data to render!".bright_red().bold());
         return;
     }
 
+    let theme = options.theme.as_str();
+    let style = options.style.to_lowercase();
+    let scale = options.scale;
     let max_contributions = get_max_height(contributions);
-    let target_height: u32 = 30;
-    let building_heights: Vec<u32> = compute_building_heights(contributions, max_contributions, target_height, scale);
 
     let total_contributions: u32 = contributions.iter().sum();
-    let moon_type = select_moon_type(total_contributions);
-    let max_height = *building_heights.iter().max().unwrap_or(&1) + 6;
+    let moon_type = moon_for(&options.moon, total_contributions, dates);
 
     print_header();
     println!("{}", format!("📈 Max daily contributions: {}", max_contributions).bright_yellow().bold());
+    if let Some(reference) = &options.reference {
+        println!("{}", format!("📏 Shared scale: {} (busiest day {})", reference.label, reference.max()).bright_yellow().bold());
+    }
     println!("{}", format!("⚖️  Scale: {}    🎭 Style: {}", scale, style).bright_cyan().bold());
-    print_skyline_title(style);
+    print_skyline_title(&style);
 
   
-    let mut width = building_heights.len().min(25);
-    if let Some(w) = width_opt { width = building_heights.len().min(w); }
+    let width = options.shown_days(contributions.len());
 
-    
-    if sky_mode != "none" {
-        let cols = match style.to_lowercase().as_str() {
-            "braille" => width * 4,
-            _ => width * 3,
-        };
-        print_night_sky(cols, &moon_type);
-    }
+    match style.as_str() {
+        "iso" => {
+            let (lines, cols) = render_iso_lines(contributions, dates, options);
+            let layout = SkyLayout::unaligned(contributions, dates, cols, 5, options.ascii_only);
+            print_sky(&options.sky_mode, &layout, &moon_type, theme, options.seed);
+            for line in &lines {
+                println!("{}", line);
+            }
+        }
+        "heatmap" => {
+            println!();
+            for line in render_heatmap_lines(contributions, dates, options.ascii_only, theme) {
+                println!("{}", line);
+            }
+        }
+        _ => {
+            let layout = SkyLayout::new(contributions, dates, width, options.cell, 0, options.ascii_only);
+            print_sky(&options.sky_mode, &layout, &moon_type, theme, options.seed);
 
-  
-    match style.to_lowercase().as_str() {
-        "ascii" => render_ascii_skyline(&building_heights, contributions, max_height, width, theme),
-        "blocks" => render_blocks_skyline(&building_heights, contributions, max_height, width, ascii_only, theme),
-        "hash" => render_hash_skyline(&building_heights, contributions, max_height, width, theme),
-        _ => render_braille_skyline(&building_heights, contributions, max_height, &moon_type, theme, width),
+            for line in building_lines(contributions, dates, options, width) {
+                println!("{}", line);
+            }
+        }
     }
-
-    print_ground_section(width);
-    print_statistics(contributions, max_contributions);
+    print_statistics(contributions, dates, options.rendered_days(contributions.len()));
+    print_insights(contributions, dates, options.ascii_only);
 
     let achievements = crate::achievements::calculate_achievements(contributions);
     crate::achievements::display_achievements(&achievements);
 
-    print_legend();
+    print_legend(contributions, dates, options);
     print_footer();
 }
 
+/// Styles that draw one building per day, left to right.
+pub const COLUMN_STYLES: [&str; 4] = ["braille", "blocks", "ascii", "hash"];
+
+/// The building rows and ground of a 2D style for the most recent `width`
+/// days, colored and ready to print.
+pub(crate) fn building_lines(contributions: &[u32], dates: &[String], options: &RenderOptions, width: usize) -> Vec<String> {
+    building_frame(contributions, dates, options, width, |_, height| height, |_, height| height)
+}
+
+/// `building_lines` while the city is going up: `built` and `lit` give how
+/// many rows of each shown day's building stand so far and how many of them
+/// have their lights on. Every frame has as many rows as the finished city.
+pub(crate) fn building_frame(
+    contributions: &[u32],
+    dates: &[String],
+    options: &RenderOptions,
+    width: usize,
+    built: impl Fn(usize, u32) -> u32,
+    lit: impl Fn(usize, u32) -> u32,
+) -> Vec<String> {
+    let target_height = options.height;
+    let height_scale = options.height_scale(contributions, target_height);
+    let building_heights: Vec<u32> = contributions.iter().map(|&count| height_scale.height(count)).collect();
+    let max_height = *building_heights.iter().max().unwrap_or(&1) + headroom(target_height);
+
+    // Show the most recent `width` days.
+    let offset = contributions.len() - width;
+    let heights: Vec<u32> = building_heights[offset..].iter().enumerate().map(|(i, &height)| built(i, height)).collect();
+    let lit_floors: Vec<u32> = building_heights[offset..].iter().enumerate().map(|(i, &height)| lit(i, height)).collect();
+    let visible = &contributions[offset..];
+    let visible_dates = &dates[offset.min(dates.len())..];
+    let colors = BuildingColors::new(&options.theme, &options.color_by, contributions, target_height)
+        .daylight(options.sky_mode == "day")
+        .lit_floors(lit_floors)
+        .clipped_above(Some(height_scale.cap()));
+    let cell = options.cell;
+    let mut lines = match options.style.to_lowercase().as_str() {
+        "ascii" => render_ascii_skyline(&heights, visible, visible_dates, max_height, &colors, cell),
+        "blocks" => render_blocks_skyline(&heights, visible, visible_dates, max_height, &colors, cell, options.ascii_only),
+        "hash" => render_hash_skyline(&heights, visible, visible_dates, max_height, &colors, cell),
+        _ => render_braille_skyline(&heights, visible, visible_dates, max_height, &colors, cell),
+    };
+    let columns = cell.columns(width);
+    let ground = ground_lines(&options.ground(), &lines, columns, &colors.theme, options.ascii_only, options.seed);
+    lines.extend(ground);
+    lines
+}
+
 
 
 fn render_braille_skyline(
     building_heights: &[u32],
     contributions: &[u32],
+    dates: &[String],
     max_height: u32,
-    _moon_type: &crate::renderer::sky_elements::MoonType,
-    theme: &str,
-    width: usize,
-) {
-    let total_contributions: u32 = contributions.iter().sum();
-    let longest_streak = calculate_longest_streak(contributions);
-    
+    colors: &BuildingColors,
+    cell: CellSize,
+) -> Vec<String> {
+    let landmarks = landmark_arts(contributions, dates, "braille", cell);
+    let mut lines = Vec::new();
     for row in (1..=max_height).rev() {
         let mut line = String::new();
-        for i in 0..width {
-            let day_contributions = contributions[contributions.len().saturating_sub(width) + i];
-            
+        for (i, &height) in building_heights.iter().enumerate() {
+            let day_contributions = contributions[i];
+            let date = dates.get(i).map(String::as_str);
             
-            if let Some(special_building) = get_special_building(i, day_contributions, total_contributions, longest_streak) {
-                let special_height = special_building.len() as u32;
-                if row <= special_height {
-                    let building_line_index = (special_height - row) as usize;
-                    line.push_str(&special_building[building_line_index]);
-                } else {
-                    line.push_str("   ");
-                }
+            if let Some(art) = &landmarks[i] {
+                line.push_str(&get_special_building(art, height, row, day_contributions, date, colors));
             } else {
-
-                let height = building_heights[i];
-                let building_part = get_building_part(height, row, day_contributions, theme);
+                let building_part = get_building_part(height, row, i, day_contributions, date, colors, cell);
                 line.push_str(&building_part);
             }
-            if i < width - 1 { line.push(' '); }
+            if i < building_heights.len() - 1 { line.push_str(&cell.spacer()); }
         }
-        println!("{}", line);
+        lines.push(line);
     }
+    lines
 }
 
-fn render_ascii_skyline(building_heights: &[u32], contributions: &[u32], max_height: u32, width: usize, theme: &str) {
-    let theme = get_theme(theme);
+fn render_ascii_skyline(building_heights: &[u32], contributions: &[u32], dates: &[String], max_height: u32, colors: &BuildingColors, cell: CellSize) -> Vec<String> {
+    let theme = &colors.theme;
+    let landmarks = landmark_arts(contributions, dates, "ascii", cell);
+    let mut lines = Vec::new();
     for row in (1..=max_height).rev() {
         let mut line = String::new();
-        for i in 0..width {
-            let height = building_heights[i];
-            if row > height {
-                line.push_str("   ");
+        for (i, &height) in building_heights.iter().enumerate() {
+            if let Some(art) = &landmarks[i] {
+                line.push_str(&get_special_building(art, height, row, contributions[i], dates.get(i).map(String::as_str), colors));
+            } else if row > height {
+                line.push_str(&cell.blank());
+            } else if row == height && colors.clipped(contributions[i]) {
+                line.push_str(&theme.antenna_color.paint(&cell.stretch(style_glyphs("ascii", false).broken)).to_string());
             } else if row == height {
-                let roof = (theme.roof_color)("/\\/").to_string();
+                let roof = theme.roof_color.paint(&cell.stretch("/\\/")).to_string();
                 line.push_str(&roof);
             } else if row == 1 {
-                let base = (theme.base_color)("‾‾‾").to_string();
+                let base = theme.base_color.paint(&cell.stretch("‾‾‾")).to_string();
                 line.push_str(&base);
             } else {
-                let day_contrib = contributions[contributions.len().saturating_sub(width) + i];
-                let show_window = (row + i as u32) % 2 == 0 || day_contrib > 0;
+                let day_contrib = contributions[i];
+                let show_window = (row + i as u32).is_multiple_of(2) || colors.lit(day_contrib, i, row);
                 let body = if show_window { "|[]" } else { "| |" };
-                let bucket = match height {
-                    h if h > 25 => 5,
-                    h if h > 20 => 4,
-                    h if h > 15 => 3,
-                    h if h > 10 => 2,
-                    h if h > 5  => 1,
-                    _           => 0,
-                };
-                let building_color = theme.building_colors[bucket.min(theme.building_colors.len()-1)];
-                line.push_str(&building_color(body).to_string());
+                let building_color = colors.building(height, day_contrib, dates.get(i).map(String::as_str));
+                line.push_str(&building_color.paint(&cell.stretch(body)).to_string());
             }
-            if i < width - 1 { line.push(' '); }
+            if i < building_heights.len() - 1 { line.push_str(&cell.spacer()); }
         }
-        println!("{}", line);
+        lines.push(line);
     }
+    lines
 }
 
 fn render_blocks_skyline(
     building_heights: &[u32],
     contributions: &[u32],
+    dates: &[String],
     max_height: u32,
-    width: usize,
+    colors: &BuildingColors,
+    cell: CellSize,
     ascii_only: bool,
-    theme: &str,
-) {
+) -> Vec<String> {
 
-    let theme = get_theme(theme);
+    let theme = &colors.theme;
+    let landmarks = landmark_arts(contributions, dates, landmark_style("blocks", ascii_only), cell);
+    let mut lines = Vec::new();
     for row in (1..=max_height).rev() {
         let mut line = String::new();
-        for i in 0..width {
-            let h = building_heights[i];
-            if row > h {
-                line.push_str("   ");
+        for (i, &h) in building_heights.iter().enumerate() {
+            if let Some(art) = &landmarks[i] {
+                line.push_str(&get_special_building(art, h, row, contributions[i], dates.get(i).map(String::as_str), colors));
+            } else if row > h {
+                line.push_str(&cell.blank());
+            } else if row == h && colors.clipped(contributions[i]) {
+                line.push_str(&theme.antenna_color.paint(&cell.stretch(style_glyphs("blocks", ascii_only).broken)).to_string());
             } else if row == h {
-                let roof = if ascii_only { "^^^".to_string() } else { "▀▀▀".to_string() };
-                let roof_col = (theme.roof_color)(&roof);
+                let roof = if ascii_only { cell.stretch("^^^") } else { cell.stretch("▀▀▀") };
+                let roof_col = theme.roof_color.paint(&roof);
                 line.push_str(&roof_col.to_string());
             } else if row == 1 {
-                let base = if ascii_only { "===".to_string() } else { "███".to_string() };
-                let base_col = (theme.base_color)(&base);
+                let base = if ascii_only { cell.stretch("===") } else { cell.stretch("███") };
+                let base_col = theme.base_color.paint(&base);
                 line.push_str(&base_col.to_string());
             } else {
-                let bucket = match h {
-                    hh if hh > 25 => 5,
-                    hh if hh > 20 => 4,
-                    hh if hh > 15 => 3,
-                    hh if hh > 10 => 2,
-                    hh if hh > 5  => 1,
-                    _             => 0,
-                };
-                let building_color = theme.building_colors[bucket.min(theme.building_colors.len()-1)];
+                let day_contrib = contributions[i];
+                let building_color = colors.building(h, day_contrib, dates.get(i).map(String::as_str));
                 let mut trio = if ascii_only {
-                    match bucket {
+                    // The ASCII fill pattern still follows the building's height.
+                    match colors.height_band(h, 6) {
                         0 => ":::",
                         1 => "===",
                         2 => "***",
@@ -340,107 +325,273 @@ fn render_blocks_skyline(
                         4 => "+++",
                         _ => "|||",
                     }
-                    .to_string()
                 } else {
-                    "███".to_string()
+                    "███"
                 };
-                let day_contrib = contributions[contributions.len().saturating_sub(width) + i];
-                if day_contrib > 0 && row % 3 == 0 {
-                    trio = if ascii_only { "| |".into() } else { "█ █".into() };
+                if colors.lit(day_contrib, i, row) && row % 3 == 0 {
+                    trio = if ascii_only { "| |" } else { "█ █" };
                 }
-                line.push_str(&building_color(&trio).to_string());
+                line.push_str(&building_color.paint(&cell.stretch(trio)).to_string());
             }
-            if i < width - 1 { line.push(' '); }
+            if i < building_heights.len() - 1 { line.push_str(&cell.spacer()); }
         }
-        println!("{}", line);
+        lines.push(line);
     }
+    lines
 }
 
 fn render_hash_skyline(
     building_heights: &[u32],
     contributions: &[u32],
+    dates: &[String],
     max_height: u32,
-    width: usize,
-    theme: &str,
-) {
-    let theme = get_theme(theme);
+    colors: &BuildingColors,
+    cell: CellSize,
+) -> Vec<String> {
+    let theme = &colors.theme;
+    let landmarks = landmark_arts(contributions, dates, "hash", cell);
+    let mut lines = Vec::new();
     for row in (1..=max_height).rev() {
         let mut line = String::new();
-        for i in 0..width {
-            let h = building_heights[i];
-            if row > h {
-                line.push_str("   ");
+        for (i, &h) in building_heights.iter().enumerate() {
+            if let Some(art) = &landmarks[i] {
+                line.push_str(&get_special_building(art, h, row, contributions[i], dates.get(i).map(String::as_str), colors));
+            } else if row > h {
+                line.push_str(&cell.blank());
+            } else if row == h && colors.clipped(contributions[i]) {
+                line.push_str(&theme.antenna_color.paint(&cell.stretch(style_glyphs("hash", false).broken)).to_string());
             } else if row == h {
-                line.push_str(&(theme.roof_color)("###").to_string());
+                line.push_str(&theme.roof_color.paint(&cell.stretch("###")).to_string());
             } else if row == 1 {
-                line.push_str(&(theme.base_color)("###").to_string());
+                line.push_str(&theme.base_color.paint(&cell.stretch("###")).to_string());
             } else {
-                let bucket = match h {
-                    hh if hh > 25 => 5,
-                    hh if hh > 20 => 4,
-                    hh if hh > 15 => 3,
-                    hh if hh > 10 => 2,
-                    hh if hh > 5  => 1,
-                    _             => 0,
-                };
-                let building_color = theme.building_colors[bucket.min(theme.building_colors.len()-1)];
-                let day_contrib = contributions[contributions.len().saturating_sub(width) + i];
-                let body = if day_contrib > 0 && row % 3 == 0 { "# #" } else { "###" };
-                line.push_str(&building_color(body).to_string());
+                let day_contrib = contributions[i];
+                let building_color = colors.building(h, day_contrib, dates.get(i).map(String::as_str));
+                let body = if colors.lit(day_contrib, i, row) && row % 3 == 0 { "# #" } else { "###" };
+                line.push_str(&building_color.paint(&cell.stretch(body)).to_string());
             }
-            if i < width - 1 { line.push(' '); }
+            if i < building_heights.len() - 1 { line.push_str(&cell.spacer()); }
         }
-        println!("{}", line);
+        lines.push(line);
     }
+    lines
 }
 
-fn get_building_part(height: u32, current_row: u32, contributions: u32, theme: &str) -> String {
+/// Landmark art for each shown day, fitted to the cell, or `None` for
+/// ordinary buildings.
+fn landmark_arts(contributions: &[u32], dates: &[String], style: &str, cell: CellSize) -> Vec<Option<LandmarkArt>> {
+    place_landmarks(contributions, dates)
+        .into_iter()
+        .map(|landmark| landmark.map(|landmark| landmark.art(style, cell)))
+        .collect()
+}
+
+const ISO_MAX_HEIGHT: u32 = 12;
+const ISO_WEEKS: usize = 53;
+
+type IsoCell = Option<(char, Paint)>;
+
+/// Draws the last 53 weeks as a week/weekday grid in isometric projection.
+/// Weeks run down-right and weekdays down-left; cells are painted back to
+/// front so nearer towers hide the ones behind them. Returns the lines and
+/// the canvas width in columns.
+pub(crate) fn render_iso_lines(
+    contributions: &[u32],
+    dates: &[String],
+    options: &RenderOptions,
+) -> (Vec<String>, usize) {
+    let canvas = iso_canvas(contributions, dates, options);
+    let width = canvas.first().map_or(0, Vec::len);
+    let lines = canvas
+        .iter()
+        .skip_while(|row| row.iter().all(|cell| cell.is_none()))
+        .map(|row| {
+            let mut line = String::new();
+            for cell in row {
+                match cell {
+                    Some((glyph, color)) => line.push_str(&color.paint(&glyph.to_string()).to_string()),
+                    None => line.push(' '),
+                }
+            }
+            line.trim_end().to_string()
+        })
+        .collect();
+    (lines, width)
+}
+
+/// The canvas behind `render_iso_lines`. A week moves two columns right and
+/// one row down, a weekday two columns left and one row down, so both axes
+/// recede alike.
+fn iso_canvas(contributions: &[u32], dates: &[String], options: &RenderOptions) -> Vec<Vec<IsoCell>> {
+    let colors = BuildingColors::new(&options.theme, &options.color_by, contributions, ISO_MAX_HEIGHT)
+        .daylight(options.sky_mode == "day");
+    let theme = &colors.theme;
+    let days: Vec<usize> = (0..contributions.len()).collect();
+    let grid = weekly_grid(&days, dates);
+    let weeks = &grid[grid.len().saturating_sub(ISO_WEEKS)..];
+    let scale = options.height_scale(contributions, ISO_MAX_HEIGHT);
+    let (left_glyph, right_glyph, roof_glyph, tile_glyph) = if options.ascii_only {
+        ('#', ':', '_', '.')
+    } else {
+        ('█', '▓', '▄', '▁')
+    };
+
+    let width = 2 * weeks.len() + 12;
+    let depth_rows = weeks.len() + 7;
+    let mut canvas: Vec<Vec<IsoCell>> = vec![vec![None; width]; ISO_MAX_HEIGHT as usize + depth_rows];
+
+    let mut cells: Vec<(usize, usize, usize)> = weeks
+        .iter()
+        .enumerate()
+        .flat_map(|(w, week)| week.iter().enumerate().filter_map(move |(d, day)| day.map(|c| (w, d, c))))
+        .collect();
+    cells.sort_by_key(|&(w, d, _)| (w + d, w));
+
+    for (w, d, day) in cells {
+        let (count, date) = (contributions[day], dates.get(day).map(String::as_str));
+        let x = 2 * (w + 6 - d);
+        let ground = ISO_MAX_HEIGHT as usize + w + d;
+        let h = scale.height(count) as usize;
+        if h == 0 {
+            canvas[ground][x] = Some((tile_glyph, theme.base_color));
+            canvas[ground][x + 1] = Some((tile_glyph, theme.base_color));
+            continue;
+        }
+
+        let left_color = colors.building(h as u32, count, date);
+        let right_color = colors.building_shade(h as u32, count, date);
+        for row in canvas.iter_mut().take(ground + 1).skip(ground + 1 - h) {
+            row[x] = Some((left_glyph, left_color));
+            row[x + 1] = Some((right_glyph, right_color));
+        }
+        canvas[ground + 1 - h][x] = Some((roof_glyph, theme.roof_color));
+        canvas[ground + 1 - h][x + 1] = Some((roof_glyph, theme.roof_color));
+    }
+
+    canvas
+}
+
+/// Draws GitHub's familiar contribution calendar: one row per weekday, one
+/// column per week, coloured by contribution level along the theme's ramp.
+pub(crate) fn render_heatmap_lines(
+    contributions: &[u32],
+    dates: &[String],
+    ascii_only: bool,
+    theme: &str,
+) -> Vec<String> {
     let theme = get_theme(theme);
-    let color_idx = match height {
-        h if h > 25 => 5,
-        h if h > 20 => 4,
-        h if h > 15 => 3,
-        h if h > 10 => 2,
-        h if h > 5  => 1,
-        _           => 0,
+    let thresholds = level_thresholds(contributions);
+    let count_grid = weekly_grid(contributions, dates);
+    let date_grid = weekly_grid(dates, dates);
+    let start = count_grid.len().saturating_sub(ISO_WEEKS);
+    let (weeks, week_dates) = (&count_grid[start..], &date_grid[start..]);
+
+    let glyphs: [&str; 5] = if ascii_only { [".", "-", "+", "*", "#"] } else { ["▪", "■", "■", "■", "■"] };
+    let ramp = &theme.building_colors;
+    let paint = |level: usize, text: &str| -> String {
+        if level == 0 {
+            theme.base_color.paint(text).to_string()
+        } else {
+            ramp[(level * (ramp.len() - 1)) / 4].paint(text).to_string()
+        }
     };
-    let building_color = theme.building_colors[color_idx.min(theme.building_colors.len()-1)];
-    let window_color = theme.window_colors[color_idx.min(theme.window_colors.len()-1)];
+
+    let mut lines = Vec::new();
+
+    let mut header = String::from("    ");
+    let mut last_month = None;
+    for (w, days) in week_dates.iter().enumerate() {
+        let month = days.iter().flatten().next().and_then(|d| month_of(d));
+        let column = 4 + w * 2;
+        if month.is_some() && month != last_month && header.chars().count() <= column {
+            header.push_str(&" ".repeat(column - header.chars().count()));
+            header.push_str(month_abbrev(month.unwrap_or(1)));
+        }
+        last_month = month;
+    }
+    lines.push(header.bright_white().bold().to_string());
+
+    const WEEKDAY_LABELS: [&str; 7] = ["   ", "Mon", "   ", "Wed", "   ", "Fri", "   "];
+    for (day, label) in WEEKDAY_LABELS.iter().enumerate() {
+        let mut line = format!("{} ", label.bright_white());
+        for week in weeks {
+            match week[day] {
+                Some(count) => {
+                    let level = contribution_level(count, &thresholds);
+                    line.push_str(&paint(level, glyphs[level]));
+                    line.push(' ');
+                }
+                None => line.push_str("  "),
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    let mut legend = format!("\n{}", " ".repeat((4 + weeks.len() * 2).saturating_sub(19)));
+    legend.push_str(&"Less ".bright_white().to_string());
+    for (level, glyph) in glyphs.iter().enumerate() {
+        legend.push_str(&paint(level, glyph));
+        legend.push(' ');
+    }
+    legend.push_str(&"More".bright_white().to_string());
+    lines.push(legend);
+    lines
+}
+
+/// Antenna rows on top of a braille tower. The 5, 3 and 1 row antennas on
+/// towers taller than 25, 18 and 12 rows were drawn for a 30-row skyline;
+/// both the thresholds and the lengths scale with `target_height`.
+fn antenna_rows(height: u32, target_height: u32) -> u32 {
+    let scaled = |rows: u32| (rows * target_height).div_ceil(30).max(1);
+    match height * 30 {
+        h if h > 25 * target_height => scaled(5),
+        h if h > 18 * target_height => scaled(3),
+        h if h > 12 * target_height => scaled(1),
+        _ => 0,
+    }
+}
+
+/// Rows above the tallest possible tower: room for its antenna and one more.
+fn headroom(target_height: u32) -> u32 {
+    antenna_rows(target_height, target_height) + 1
+}
+
+pub(crate) fn get_building_part(height: u32, current_row: u32, day: usize, contributions: u32, date: Option<&str>, colors: &BuildingColors, cell: CellSize) -> String {
+    let theme = &colors.theme;
+    let building_color = colors.building(height, contributions, date);
+    let window_color = colors.window(height, contributions, date);
     let antenna_color = theme.antenna_color;
     let roof_color = theme.roof_color;
     let base_color = theme.base_color;
-    let antenna_height = match height {
-        h if h > 25 => 5,
-        h if h > 18 => 3, 
-        h if h > 12 => 1,
-        _ => 0,
-    };
+    let antenna_height = antenna_rows(height, colors.target_height());
+    if colors.clipped(contributions) && current_row >= height {
+        return if current_row == height {
+            antenna_color.paint(&cell.stretch(style_glyphs("braille", false).broken)).to_string()
+        } else {
+            cell.blank()
+        };
+    }
     if antenna_height > 0 && current_row > height && current_row <= height + antenna_height {
         return match current_row - height {
-            1 => antenna_color(" ⢰ ").to_string(),
-            2 => antenna_color(" ⢸ ").to_string(),
-            _ => antenna_color(" ⢸ ").to_string(),
+            1 => antenna_color.paint(&cell.center(" ⢰ ")).to_string(),
+            2 => antenna_color.paint(&cell.center(" ⢸ ")).to_string(),
+            _ => antenna_color.paint(&cell.center(" ⢸ ")).to_string(),
         };
     }
-    if current_row > height { return "   ".to_string(); }
+    if current_row > height { return cell.blank(); }
     if current_row == 1 && height > 0 {
-        return base_color("⣸⣸⣸").to_string();
+        return base_color.paint(&cell.stretch("⣸⣸⣸")).to_string();
     }
     if current_row == height {
-        return roof_color("¯¯¯").to_string();
+        return roof_color.paint(&cell.stretch("¯¯¯")).to_string();
     }
-    let is_window = contributions > 0 && 
-        ((current_row + contributions * 3) % 3 == 0 || 
-         (current_row % 4 == 0 && contributions % 2 == 1));
+    let is_window = colors.lit(contributions, day, current_row) && 
+        ((current_row + contributions * 3).is_multiple_of(3) || 
+         (current_row.is_multiple_of(4) && contributions % 2 == 1));
     if is_window {
-        return window_color("⣾⣾⣾").to_string();
+        return window_color.paint(&cell.stretch("⣾⣾⣾")).to_string();
     }
-    building_color("⣿⣿⣿").to_string()
-}
-
-fn get_special_building(_display_index: usize, _contributions: u32, _total_contributions: u32, _longest_streak: u32) -> Option<Vec<String>> {
-   
-    None
+    building_color.paint(&cell.stretch("⣿⣿⣿")).to_string()
 }
 
 
@@ -457,83 +608,364 @@ fn print_skyline_title(style: &str) {
         "ascii" => "│                     ASCII SKYLINE                          │",
         "blocks" => "│             THEMED BLOCK BUILDINGS SKYLINE                │",
         "hash" => "│                 HASH-THEMED ASCII SKYLINE                 │",
+        "iso" => "│                ISOMETRIC CONTRIBUTION CITY                 │",
+        "heatmap" => "│                 CONTRIBUTION CALENDAR HEATMAP               │",
         _ => "│                 BRAILLE-STYLE ASCII SKYLINE                 │",
     };
     println!("{}", title.bright_magenta().bold());
     println!("{}", "└─────────────────────────────────────────────────────────────┘".bright_magenta().bold());
 }
 
-fn print_ground_section(width: usize) {
-    let line_width = (width * 3) + (width - 1);
-    let mut ground_line = String::new();
-    for i in 0..line_width {
-        let char = match i % 3 {
-            0 => "~".bright_blue().to_string(),
-            1 => "~".blue().to_string(), 
-            _ => "~".bright_cyan().to_string(),
-        };
-        ground_line.push_str(&char);
-    }
-    println!("{}", ground_line);
-}
-
-fn print_statistics(contributions: &[u32], max_contributions: u32) {
-    let total: u32 = contributions.iter().sum();
-    let avg = total as f32 / contributions.len() as f32;
-    let active_days = contributions.iter().filter(|&&x| x > 0).count();
-    let longest_streak = calculate_longest_streak(contributions);
+pub(crate) fn print_statistics(contributions: &[u32], dates: &[String], displayed: usize) {
+    let stats = Statistics::new(contributions, dates);
+    let Statistics { total, average: avg, max_day: max_contributions, active_days, .. } = stats;
+    // Streak rows carry their dates, so pad them to the box edge.
+    let run_row = |label: &str, run: &Run, paint: Color| {
+        let range = run.range().map(|range| format!("  {}", range)).unwrap_or_default();
+        let width = format!("│ {} {:>3} days{}", label, run.days, range).chars().count();
+        format!(
+            "│ {} {} days{}{}│",
+            label,
+            format!("{:>3}", run.days).color(paint).bold(),
+            range.bright_white(),
+            " ".repeat(57usize.saturating_sub(width))
+        )
+        .bright_blue()
+    };
     
     println!("\n{}", "╭─────────────────────────────────────────────────────────────╮".bright_blue().bold());
     println!("{}", "│               📊 CODING STATISTICS 📊                       │".bright_blue().bold());
     println!("{}", "├─────────────────────────────────────────────────────────────┤".bright_blue().bold());
     
     println!("{}", format!("│ 📅 Total days tracked: {}                              │", format!("{:>3}", contributions.len()).bright_white().bold()).bright_blue());
-    println!("{}", format!("│ 🏙️  Days displayed:  {}                                 │", format!("{:>3}", contributions.len().min(25)).bright_white().bold()).bright_blue());
+    println!("{}", format!("│ 🏙️  Days displayed:  {}                                 │", format!("{:>3}", displayed).bright_white().bold()).bright_blue());
     println!("{}", format!("│ ⭐ Total contributions: {}                             │", format!("{:>4}", total).bright_yellow().bold()).bright_blue());
-    println!("{}", format!("│ 📈 Average per day: {:.1}                                │", format!("{:>4.1}", avg).bright_cyan().bold()).bright_blue());
+    println!("{}", format!("│ 📈 Average per day: {}                                │", format!("{:>4.1}", avg).bright_cyan().bold()).bright_blue());
     println!("{}", format!("│ 🔥 Max daily contributions:  {}                        │", format!("{:>3}", max_contributions).bright_red().bold()).bright_blue());
     println!("{}", format!("│ 💪 Active coding days: {} ({:.1}%)                   │", 
              format!("{:>3}", active_days).bright_green().bold(),
-             ((active_days as f32 / contributions.len() as f32) * 100.0)).bright_blue());
-    println!("{}", format!("│ 🔥 Longest streak:  {} days                            │", format!("{:>3}", longest_streak).bright_magenta().bold()).bright_blue());
+             stats.active_percent()).bright_blue());
+    println!("{}", run_row("🔥 Longest streak: ", &stats.longest_streak, Color::BrightMagenta));
+    println!("{}", run_row("⚡ Current streak: ", &stats.current_streak, Color::BrightMagenta));
+    println!("{}", run_row("😴 Longest gap:    ", &stats.longest_gap, Color::BrightBlack));
     
     println!("{}", "╰─────────────────────────────────────────────────────────────╯".bright_blue().bold());
+    if stats.streak_at_risk {
+        println!(
+            "{}",
+            format!("⚠️  Streak at risk: no contributions yet today, contribute to keep your {}-day streak!", stats.current_streak.days)
+                .bright_yellow()
+                .bold()
+        );
+    }
 }
 
-fn calculate_longest_streak(contributions: &[u32]) -> u32 {
-    let mut longest = 0;
-    let mut current = 0;
-    
-    for &count in contributions {
-        if count > 0 {
-            current += 1;
-            longest = longest.max(current);
-        } else {
-            current = 0;
+/// Weekday and month charts, typical days and recent trends, as printed
+/// under the statistics box.
+pub(crate) fn insight_lines(contributions: &[u32], dates: &[String], ascii_only: bool) -> Vec<String> {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const BAR_WIDTH: usize = 24;
+    let insights = Insights::new(contributions, dates);
+    let mut lines = vec![String::new(), "📈 INSIGHTS".bright_blue().bold().to_string()];
+
+    if !dates.is_empty() {
+        let busiest = insights.weekdays.iter().copied().max().unwrap_or(0);
+        lines.push("📆 By weekday".bright_cyan().bold().to_string());
+        for (name, &total) in WEEKDAYS.iter().zip(&insights.weekdays) {
+            lines.push(format!("   {} {} {}", name.bright_white(), bar(total, busiest, BAR_WIDTH, ascii_only).bright_cyan(), format!("{:>6}", total).bright_yellow()));
+        }
+        let busiest = insights.months.iter().map(|month| month.total).max().unwrap_or(0);
+        lines.push("🗓️  By month".bright_cyan().bold().to_string());
+        for month in &insights.months {
+            lines.push(format!("   {} {} {}", format!("{:<8}", month.label()).bright_white(), bar(month.total, busiest, BAR_WIDTH, ascii_only).bright_magenta(), format!("{:>6}", month.total).bright_yellow()));
         }
     }
-    longest
+    if let Some(month) = &insights.busiest_month {
+        lines.push(format!("🏆 Busiest month: {} ({} contributions)", month.label(), month.total).bright_green().bold().to_string());
+    }
+    lines.push(format!("📊 Typical active day: median {} · p90 {}", insights.median, insights.p90).bright_white().to_string());
+    lines.push(format!("〰️  Average per day: {:.1} over the last 7 days · {:.1} over the last 30", insights.rolling_7, insights.rolling_30).bright_white().to_string());
+    // One mark per week, ending on the last day.
+    let rolling = rolling_average(contributions, 7);
+    let weekly: Vec<f32> = rolling.iter().rev().step_by(7).rev().copied().collect();
+    if weekly.len() > 1 {
+        lines.push(format!("   {} {}", sparkline(&weekly, ascii_only).bright_cyan(), "7-day average, week by week".bright_black()));
+    }
+    let trend = &insights.week_over_week;
+    let change = match trend.change_percent {
+        Some(change) if change > 0.0 => format!("+{:.0}%", change).bright_green().bold(),
+        Some(change) if change < 0.0 => format!("{:.0}%", change).bright_red().bold(),
+        _ if trend.this_week == trend.last_week => "no change".bright_black(),
+        _ => "new activity".bright_black(),
+    };
+    lines.push(format!("{} {}", format!("🔁 Week over week: {} this week vs {} last week", trend.this_week, trend.last_week).bright_white(), change));
+    lines
 }
 
-fn print_legend() {
-    println!("\n{}", "┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┓".bright_green().bold());
-    println!("{}", "┃              SYNTHWAVE ARCHITECTURE LEGEND                ┃".bright_green().bold());
-    println!("{}", "┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫".bright_green().bold());
-    
-    println!("{}", format!("┃ {}  NEON SPIRE (26+) w/ Antenna ⢰                  ┃", "⣿⣿⣿".bright_magenta().bold()).bright_green());
-    println!("{}", format!("┃ {}  MEGA TOWER (21-25) w/ Antenna ⢸                ┃", "⣿⣿⣿".magenta().bold()).bright_green());
-    println!("{}", format!("┃ {}  Cyber Corp (16-20) w/ Roof ¯¯¯                ┃", "⣿⣿⣿".bright_blue().bold()).bright_green());
-    println!("{}", format!("┃ {}  Data Hub (11-15)                              ┃", "⣿⣿⣿".blue().bold()).bright_green());
-    println!("{}", format!("┃ {}  Apartments (6-10)                             ┃", "⣿⣿⣿".bright_cyan().bold()).bright_green());
-    println!("{}", format!("┃ {}  Shops (1-5)                                   ┃", "⣿⣿⣿".cyan().bold()).bright_green());
-    
-    println!("{}", "┣━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┫".bright_green().bold());
-    println!("{}", "┃ Features: ⣸ Base, ⣾ Windows, ~~~ Water                  ┃".bright_green().bold());
-    println!("{}", "┃ Sky: . * + Stars                                            ┃".bright_green().bold());
-    println!("{}", "┃ Moons (by total contributions):                             ┃".bright_green().bold());
-    println!("{}", "┃   0-499: Crescent  500-1499: Quarter                      ┃".bright_green().bold());
-    println!("{}", "┃   1500-2999: Gibbous  3000+: Full Moon                    ┃".bright_green().bold());
-    println!("{}", "┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛".bright_green().bold());
+pub(crate) fn print_insights(contributions: &[u32], dates: &[String], ascii_only: bool) {
+    for line in insight_lines(contributions, dates, ascii_only) {
+        println!("{}", line);
+    }
+}
+
+/// `value` as a bar `width` columns long at `max`.
+fn bar(value: u32, max: u32, width: usize, ascii_only: bool) -> String {
+    let (full, empty) = if ascii_only { ('#', '.') } else { ('█', '░') };
+    let filled = if max == 0 { 0 } else { (value as f32 / max as f32 * width as f32).round() as usize };
+    std::iter::repeat_n(full, filled).chain(std::iter::repeat_n(empty, width - filled)).collect()
+}
+
+/// One character per value, taller for bigger values.
+fn sparkline(values: &[f32], ascii_only: bool) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    const ASCII_BARS: [char; 8] = ['_', '.', ':', '-', '=', '+', '*', '#'];
+    let levels = if ascii_only { ASCII_BARS } else { BARS };
+    let max = values.iter().copied().fold(0.0, f32::max);
+    values
+        .iter()
+        .map(|&value| if max > 0.0 { levels[((value / max) * 7.0).round() as usize] } else { levels[0] })
+        .collect()
+}
+
+const LEGEND_WIDTH: usize = 63;
+const BUILDING_NAMES: [&str; 6] = ["Shops", "Apartments", "Data Hub", "Cyber Corp", "Mega Tower", "Neon Spire"];
+
+/// Glyphs a style draws with, so the legend shows the same shapes.
+pub(crate) struct StyleGlyphs {
+    pub(crate) body: &'static str,
+    pub(crate) window: &'static str,
+    pub(crate) roof: &'static str,
+    base: &'static str,
+    /// Roof of a building clipped by `--scale percentile:<p>`.
+    pub(crate) broken: &'static str,
+}
+
+pub(crate) fn style_glyphs(style: &str, ascii_only: bool) -> StyleGlyphs {
+    let (body, window, roof, base, broken) = match (style, ascii_only) {
+        ("blocks", false) => ("███", "█ █", "▀▀▀", "███", "▞▚▞"),
+        ("blocks", true) => ("###", "| |", "^^^", "===", "v^v"),
+        ("ascii", _) => ("| |", "|[]", "/\\/", "‾‾‾", "^v^"),
+        ("hash", _) => ("###", "# #", "###", "###", "#v#"),
+        ("iso", false) => ("█▓ ", "█▓ ", "▄▄ ", "▁▁ ", "▄▄ "),
+        ("iso", true) => ("#: ", "#: ", "__ ", ".. ", "__ "),
+        ("heatmap", false) => ("■  ", "■  ", "■  ", "▪  ", "■  "),
+        ("heatmap", true) => ("#  ", "#  ", "#  ", ".  ", "#  "),
+        _ => ("⣿⣿⣿", "⣾⣾⣾", "¯¯¯", "⣸⣸⣸", "⠾⠷⠾"),
+    };
+    StyleGlyphs { body, window, roof, base, broken }
+}
+
+/// Explains the current rendering: one row per color the buildings can take,
+/// with the contribution counts from this data that produce it.
+pub(crate) fn legend_lines(contributions: &[u32], dates: &[String], options: &RenderOptions) -> Vec<String> {
+    let style = options.style.to_lowercase();
+    let target_height = if style == "iso" { ISO_MAX_HEIGHT } else { options.height };
+    let color_by = if style == "heatmap" { "level" } else { options.color_by.as_str() };
+    let colors = BuildingColors::new(&options.theme, color_by, contributions, target_height);
+    let theme = &colors.theme;
+    let glyphs = style_glyphs(&style, options.ascii_only);
+    let max_contributions = get_max_height(contributions);
+    let scale = options.height_scale(contributions, target_height);
+    let height_of = |count: u32| scale.height(count);
+
+    let edge = || "┃".bright_green().bold().to_string();
+    let border = |left: &str, right: &str| format!("{}{}{}", left, "━".repeat(LEGEND_WIDTH), right).bright_green().bold().to_string();
+    let row = |swatch: String, text: &str| {
+        let padding = (LEGEND_WIDTH - 6).saturating_sub(text.chars().count());
+        format!("{} {}  {}{}{}", edge(), swatch, text.bright_white(), " ".repeat(padding), edge())
+    };
+    let note = |text: &str| {
+        let padding = (LEGEND_WIDTH - 1).saturating_sub(text.chars().count());
+        format!("{} {}{}{}", edge(), text.bright_green().bold(), " ".repeat(padding), edge())
+    };
+    // Counts describe the days on screen; the scale still uses the whole year.
+    let shown_days = options.rendered_days(contributions.len());
+    let shown = &contributions[contributions.len() - shown_days..];
+    let shown_dates = &dates[dates.len().saturating_sub(shown_days)..];
+    let range = |min: u32, max: u32| if min == max { format!("{}", min) } else { format!("{}-{}", min, max) };
+
+    let mut lines = vec![String::new(), border("┏", "┓")];
+    let title = format!("{} ARCHITECTURE LEGEND", theme.name.to_uppercase());
+    lines.push(note(&format!("{:^width$}", title, width = LEGEND_WIDTH - 2)));
+    lines.push(note(&format!("Colored by {} · {} scale", color_by, options.scale)));
+    if let Some(reference) = &options.reference {
+        lines.push(note(&format!("Shared scale: {} · busiest day {}", reference.label, reference.max())));
+    }
+    lines.push(border("┣", "┫"));
+
+    match color_by {
+        "weekday" | "month" => {
+            const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+            // (representative date, total contributions, active days) per weekday or month
+            let mut groups: Vec<Option<(&str, u32, usize)>> = vec![None; if color_by == "weekday" { 7 } else { 12 }];
+            for (date, &count) in shown_dates.iter().zip(shown) {
+                let key = if color_by == "weekday" { weekday(date) } else { month_of(date).map(|m| m as usize - 1) };
+                if let Some(group) = key.and_then(|k| groups.get_mut(k)) {
+                    let entry = group.get_or_insert((date.as_str(), 0, 0));
+                    entry.1 += count;
+                    entry.2 += usize::from(count > 0);
+                }
+            }
+            for (key, group) in groups.iter().enumerate() {
+                if let Some((date, total, active_days)) = group {
+                    let label = if color_by == "weekday" { WEEKDAYS[key] } else { month_abbrev(key as u32 + 1) };
+                    let swatch = colors.building(0, 0, Some(date)).paint(glyphs.body).to_string();
+                    lines.push(row(swatch, &format!("{:<10} {} contributions · {} active days", label, total, active_days)));
+                }
+            }
+        }
+        "level" => {
+            let thresholds = level_thresholds(contributions);
+            let mut levels: [Option<(u32, u32, usize)>; 5] = [None; 5];
+            for &count in shown.iter().filter(|&&c| c > 0) {
+                let entry = levels[contribution_level(count, &thresholds)].get_or_insert((count, count, 0));
+                *entry = (entry.0.min(count), entry.1.max(count), entry.2 + 1);
+            }
+            for (level, entry) in levels.iter().enumerate().rev() {
+                if let Some((min, max, days)) = entry {
+                    let swatch = colors.building(height_of(*min), *min, None).paint(glyphs.body).to_string();
+                    lines.push(row(swatch, &format!("Level {} · {}/day · {} days", level, range(*min, *max), days)));
+                }
+            }
+        }
+        _ => {
+            let bands = theme.building_colors.len();
+            let mut buckets: Vec<Option<(u32, u32, usize)>> = vec![None; bands];
+            for &count in shown.iter().filter(|&&c| c > 0) {
+                let entry = buckets[colors.height_band(height_of(count), bands)].get_or_insert((count, count, 0));
+                *entry = (entry.0.min(count), entry.1.max(count), entry.2 + 1);
+            }
+            for (band, entry) in buckets.iter().enumerate().rev() {
+                if let Some((min, max, days)) = entry {
+                    let body = if style == "blocks" && options.ascii_only {
+                        [":::", "===", "***", "@@@", "+++", "|||"][colors.height_band(height_of(*max), 6)]
+                    } else {
+                        glyphs.body
+                    };
+                    // Gradients are shown from the band's lowest to its highest count.
+                    let counts = [*min, (*min + *max) / 2, *max];
+                    let swatch: String = body.chars().zip(counts)
+                        .map(|(glyph, count)| colors.building(height_of(count), count, None).paint(&glyph.to_string()).to_string())
+                        .collect();
+                    let name = BUILDING_NAMES[band * BUILDING_NAMES.len() / bands];
+                    lines.push(row(swatch, &format!("{:<10} {}/day · {} days", name, range(*min, *max), days)));
+                }
+            }
+        }
+    }
+
+    lines.push(border("┣", "┫"));
+    if style == "heatmap" {
+        lines.push(row(theme.base_color.paint(glyphs.base).to_string(), "No contributions"));
+    } else {
+        lines.push(row(theme.base_color.paint(glyphs.base).to_string(), "Base"));
+        if style != "iso" {
+            let lit_height = height_of(max_contributions);
+            let windows = if options.sky_mode == "day" { "Windows (lights off in daylight)" } else { "Windows (lit on days with contributions)" };
+            lines.push(row(colors.window(lit_height, max_contributions, None).paint(glyphs.window).to_string(), windows));
+        }
+        lines.push(row(theme.roof_color.paint(glyphs.roof).to_string(), "Roof"));
+        let cap = scale.cap();
+        if style != "iso" && shown.iter().any(|&count| count > cap) {
+            let text = format!("Broken roof: over {} contributions, clipped", cap);
+            lines.push(row(theme.antenna_color.paint(glyphs.broken).to_string(), &text));
+        }
+        if style == "braille" {
+            lines.push(row(theme.antenna_color.paint(" ⢸ ").to_string(), "Antenna (tallest towers)"));
+        }
+        if style != "iso"
+            && let Some((swatch, text)) = ground_swatch(&options.ground(), theme, options.ascii_only)
+        {
+            lines.push(row(swatch, text));
+        }
+    }
+
+    if style != "iso" && style != "heatmap" {
+        let art = landmark_style(&style, options.ascii_only);
+        for (day, landmark) in place_landmarks(shown, shown_dates).iter().enumerate() {
+            if let Some(landmark) = landmark {
+                let when = shown_dates.get(day).map(|date| format!(" · {}", date)).unwrap_or_default();
+                let text = format!("{}: {}{} ({})", landmark.name(), landmark.meaning(), when, shown[day]);
+                lines.push(row(theme.roof_color.paint(landmark.swatch(art)).to_string(), &text));
+            }
+        }
+    }
+
+    let sky = if style == "iso" {
+        SkyLayout::unaligned(contributions, dates, 0, 0, options.ascii_only)
+    } else {
+        SkyLayout::new(contributions, dates, shown_days, options.cell, 0, options.ascii_only)
+    };
+    let faint = |text: &str| theme.sky_color.paint(text).to_string();
+    let clouds = |lines: &mut Vec<String>| {
+        if sky.clouds() > 0 {
+            lines.push(row(faint("(~)"), &format!("Clouds: {} quiet spells of {}+ days", sky.clouds(), CLOUD_GAP)));
+        }
+    };
+    match options.sky_mode.as_str() {
+        _ if style == "heatmap" => {}
+        "none" => {}
+        "day" => {
+            lines.push(row(SUN.paint("(O)").to_string(), "Sun: daytime, window lights off"));
+            clouds(&mut lines);
+        }
+        "sunset" => {
+            let bands: String = sunset_ramp(theme).iter().skip(1).map(|paint| paint.paint("─").to_string()).collect();
+            lines.push(row(bands, &format!("Sunset bands from the {} palette", theme.name)));
+        }
+        "weather" => {
+            let (name, glyphs, paint) = precipitation(&sky, theme);
+            let swatch: String = glyphs.iter().map(|glyph| paint.paint(&glyph.to_string()).to_string()).collect();
+            let text = format!("{}: {} of the last {} days without contributions", name, sky.dry_days, RECENT_DAYS);
+            lines.push(row(swatch, &text));
+            clouds(&mut lines);
+        }
+        _ => {
+            if style == "iso" {
+                let stars: String = ['*', '+', '.'].iter().enumerate()
+                    .map(|(i, star)| theme.star_colors[i % theme.star_colors.len()].paint(&star.to_string()).to_string())
+                    .collect();
+                lines.push(row(stars, "Stars"));
+            } else {
+                let (flat, falling, dot, star) = if options.ascii_only { ("-", "\\", ".", "*") } else { ("─", "╲", "·", "✦") };
+                let bright = |text: &str| theme.star_colors[0].paint(text).to_string();
+                let levels = if options.ascii_only { ['.', '+', '*'] } else { ['·', '+', '★'] };
+                let stars: String = levels.iter().enumerate()
+                    .map(|(i, star)| theme.star_colors[(i * 3 / 2) % theme.star_colors.len()].paint(&star.to_string()).to_string())
+                    .collect();
+                lines.push(row(stars, "Stars: one per active day, higher = busier"));
+                if sky.constellations() > 0 {
+                    let swatch = format!("{}{}{}", bright(star), faint(flat), bright(star));
+                    lines.push(row(swatch, &format!("Constellations: {} streaks of 2+ days", sky.constellations())));
+                }
+                if sky.records() > 0 {
+                    let swatch = format!("{}{}{}", faint(dot), faint(falling), bright(star));
+                    lines.push(row(swatch, &format!("Shooting stars: {} new personal records", sky.records())));
+                }
+                clouds(&mut lines);
+            }
+            match dates.last().filter(|_| options.moon.eq_ignore_ascii_case("astronomical")) {
+                Some(date) => {
+                    let phase = moon_for(&options.moon, 0, dates);
+                    lines.push(row(theme.moon_color.paint(" ) ").to_string(), &format!("Moon: {} on {}", phase.name(), date)));
+                }
+                None => {
+                    lines.push(row(theme.moon_color.paint(" ) ").to_string(), "Moon (by total contributions):"));
+                    lines.push(row("   ".to_string(), "  0-499: Crescent  500-1499: Quarter"));
+                    lines.push(row("   ".to_string(), "  1500-2999: Gibbous  3000+: Full Moon"));
+                }
+            }
+        }
+    }
+    lines.push(border("┗", "┛"));
+    lines
+}
+
+
+fn print_legend(contributions: &[u32], dates: &[String], options: &RenderOptions) {
+    for line in legend_lines(contributions, dates, options) {
+        println!("{}", line);
+    }
 }
 
 fn print_footer() {
@@ -550,4 +982,52 @@ fn print_footer() {
     
     let mut input = String::new();
     let _ = io::stdin().read_line(&mut input);
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two weeks from Sunday 2024-03-03 with a tall tower on the first
+    /// Monday and a short one on the second Tuesday, which stands in front
+    /// of it in the same columns, plus a stub on the second Saturday.
+    fn two_towers() -> Vec<Vec<IsoCell>> {
+        let mut contributions = vec![0; 14];
+        contributions[1] = 12;
+        contributions[9] = 4;
+        contributions[13] = 2;
+        let dates: Vec<String> = (3..=16).map(|day| format!("2024-03-{:02}", day)).collect();
+        let options = RenderOptions { scale: Scale::Linear, ..RenderOptions::default() };
+        iso_canvas(&contributions, &dates, &options)
+    }
+
+    #[test]
+    fn iso_axes_recede_alike() {
+        let canvas = two_towers();
+        // Week 0, Monday: columns 2 * (0 + 6 - 1), ground row 12 + 0 + 1.
+        // Week 1, Tuesday: columns 2 * (1 + 6 - 2), ground row 12 + 1 + 2.
+        assert_eq!(canvas[2][10].map(|(glyph, _)| glyph), Some('▄'));
+        assert_eq!(canvas[13][10].map(|(glyph, _)| glyph), Some('█'));
+        assert_eq!(canvas[12][10].map(|(glyph, _)| glyph), Some('▄'));
+        assert_eq!(canvas[15][10].map(|(glyph, _)| glyph), Some('█'));
+    }
+
+    #[test]
+    fn nearer_towers_hide_the_ones_behind() {
+        let canvas = two_towers();
+        let (back, front) = (canvas[5][10].unwrap().1, canvas[14][10].unwrap().1);
+        assert_ne!(back, front);
+        // Row 13 is inside both towers; the nearer one is drawn over it.
+        assert_eq!(canvas[13][10].unwrap().1, front);
+    }
+
+    #[test]
+    fn iso_faces_use_different_shades() {
+        let canvas = two_towers();
+        // The stub is at the dark end of the ramp, so its shade is lighter.
+        for (row, x) in [(5, 10), (14, 10), (19, 2)] {
+            let (left, right) = (canvas[row][x].unwrap(), canvas[row][x + 1].unwrap());
+            assert_eq!((left.0, right.0), ('█', '▓'));
+            assert_ne!(left.1, right.1, "row {}", row);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use colored::*;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use crate::renderer::ground::GROUND_MODES;
+
+/// Built-in themes ship as regular theme files compiled into the binary.
+const BUILTIN_THEMES: [&str; 6] = [
+    include_str!("../../themes/synthwave.toml"),
+    include_str!("../../themes/dracula.toml"),
+    include_str!("../../themes/solarized.toml"),
+    include_str!("../../themes/cyberpunk.toml"),
+    include_str!("../../themes/matrix.toml"),
+    include_str!("../../themes/sunset.toml"),
+];
+
+/// A color plus weight, written in theme files as e.g. `"bold bright_cyan"`
+/// or `"bold #ff2bd6"`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Paint {
+    pub(crate) color: Color,
+    pub(crate) bold: bool,
+}
+
+impl Paint {
+    /// Colors `text` for the current terminal, quantizing RGB colors when
+    /// it cannot show 24-bit color.
+    pub(crate) fn paint(&self, text: &str) -> ColoredString {
+        let color = match (self.color, color_depth()) {
+            (Color::TrueColor { r, g, b }, ColorDepth::Ansi256) => {
+                if !colored::control::SHOULD_COLORIZE.should_colorize() {
+                    return text.normal();
+                }
+                // `colored` has no 256-color variant, so write the escape ourselves.
+                let weight = if self.bold { "1;" } else { "" };
+                return format!("\x1b[{}38;5;{}m{}\x1b[0m", weight, nearest_ansi256([r, g, b]), text).normal();
+            }
+            (Color::TrueColor { r, g, b }, ColorDepth::Ansi16) => nearest_ansi16([r, g, b]),
+            (color, _) => color,
+        };
+        let colored = text.color(color);
+        if self.bold { colored.bold() } else { colored }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+/// Terminal color support from `COLORTERM` and `TERM`, detected once.
+pub(crate) fn color_depth() -> ColorDepth {
+    static DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+    *DEPTH.get_or_init(|| {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        let term = std::env::var("TERM").unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    })
+}
+
+const ANSI16: [Color; 16] = [
+    Color::Black, Color::Red, Color::Green, Color::Yellow,
+    Color::Blue, Color::Magenta, Color::Cyan, Color::White,
+    Color::BrightBlack, Color::BrightRed, Color::BrightGreen, Color::BrightYellow,
+    Color::BrightBlue, Color::BrightMagenta, Color::BrightCyan, Color::BrightWhite,
+];
+
+/// Approximate RGB for any color (xterm defaults for the named ones).
+pub(crate) fn color_to_rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::White => [229, 229, 229],
+        Color::BrightBlack => [127, 127, 127],
+        Color::BrightRed => [255, 0, 0],
+        Color::BrightGreen => [0, 255, 0],
+        Color::BrightYellow => [255, 255, 0],
+        Color::BrightBlue => [92, 92, 255],
+        Color::BrightMagenta => [255, 0, 255],
+        Color::BrightCyan => [0, 255, 255],
+        Color::BrightWhite => [255, 255, 255],
+        Color::TrueColor { r, g, b } => [r, g, b],
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    (0..3).map(|i| (a[i] as i32 - b[i] as i32).pow(2) as u32).sum()
+}
+
+fn nearest_ansi16(rgb: [u8; 3]) -> Color {
+    *ANSI16.iter().min_by_key(|&&color| distance(rgb, color_to_rgb(color))).unwrap()
+}
+
+/// Closest entry in the xterm 256-color palette's 6x6x6 cube or gray ramp.
+fn nearest_ansi256(rgb: [u8; 3]) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |v: u8| (0..6).min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs()).unwrap();
+    let [r, g, b] = rgb.map(level);
+    let cube = [LEVELS[r], LEVELS[g], LEVELS[b]];
+    let cube_index = 16 + 36 * r as u8 + 6 * g as u8 + b as u8;
+
+    let average = (rgb.iter().map(|&v| v as u32).sum::<u32>() / 3) as u8;
+    let gray_step = (average.saturating_sub(3) / 10).min(23);
+    let gray = 8 + 10 * gray_step;
+    if distance(rgb, [gray; 3]) < distance(rgb, cube) { 232 + gray_step } else { cube_index }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Theme {
+    pub(crate) name: String,
+    pub(crate) emoji: String,
+    pub(crate) description: String,
+    pub(crate) building_colors: Vec<Paint>,
+    pub(crate) window_colors: Vec<Paint>,
+    pub(crate) antenna_color: Paint,
+    pub(crate) roof_color: Paint,
+    pub(crate) base_color: Paint,
+    pub(crate) sky_color: Paint,
+    pub(crate) star_colors: Vec<Paint>,
+    pub(crate) moon_color: Paint,
+    pub(crate) water_colors: Vec<Paint>,
+    /// Default foreground under the buildings, one of `GROUND_MODES`.
+    pub(crate) ground: String,
+}
+
+/// On-disk theme format (TOML or JSON). Missing fields fall back to synthwave.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    emoji: Option<String>,
+    description: Option<String>,
+    buildings: Option<Vec<String>>,
+    windows: Option<Vec<String>>,
+    antenna: Option<String>,
+    roof: Option<String>,
+    base: Option<String>,
+    sky: Option<String>,
+    stars: Option<Vec<String>>,
+    moon: Option<String>,
+    water: Option<Vec<String>>,
+    ground: Option<String>,
+}
+
+static THEME_CACHE: OnceLock<Mutex<HashMap<String, Theme>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<HashMap<String, Theme>> {
+    THEME_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the named theme, falling back to synthwave for unknown names.
+/// Each name is resolved once, unknown ones included; `random` without a
+/// seed always picks the same theme, see `pick_random_theme`.
+pub(crate) fn get_theme(name: &str) -> Theme {
+    let key = name.to_lowercase();
+    if let Some(theme) = cache().lock().unwrap().get(&key) {
+        return theme.clone();
+    }
+    let theme = load_theme(&key).unwrap_or_else(|_| default_theme());
+    cache().lock().unwrap().insert(key, theme.clone());
+    theme
+}
+
+/// Resolves a theme by name from the user's themes directory or the
+/// built-ins, caching the result for later `get_theme` calls.
+pub(crate) fn load_theme(name: &str) -> Result<Theme, Box<dyn std::error::Error>> {
+    let key = name.to_lowercase();
+    if key == "random" {
+        return Ok(pick_random_theme(0));
+    }
+    let themes = available_themes();
+    let Some(theme) = themes.iter().find(|t| t.name.to_lowercase() == key).cloned() else {
+        let names: Vec<&str> = themes.iter().map(|t| t.name.as_str()).collect();
+        return Err(format!("Unknown theme '{}'. Available themes: {}, random", name, names.join(", ")).into());
+    };
+    cache().lock().unwrap().insert(key, theme.clone());
+    Ok(theme)
+}
+
+/// Picks one of the available themes from `seed`, so `--theme random` renders
+/// the same way every time for the same seed.
+pub(crate) fn pick_random_theme(seed: u64) -> Theme {
+    let theme = available_themes().choose(&mut ChaCha8Rng::seed_from_u64(seed)).cloned().unwrap_or_else(default_theme);
+    cache().lock().unwrap().insert(theme.name.to_lowercase(), theme.clone());
+    theme
+}
+
+/// Loads a theme from an explicit `.toml` or `.json` path and registers it
+/// under its name so the renderers can look it up.
+pub(crate) fn load_theme_file(path: &str) -> Result<Theme, Box<dyn std::error::Error>> {
+    let theme = read_theme_file(Path::new(path))?;
+    cache().lock().unwrap().insert(theme.name.to_lowercase(), theme.clone());
+    Ok(theme)
+}
+
+/// Built-in themes followed by the user's own; a user theme with the same
+/// name as a built-in replaces it. The themes directory is read once, and
+/// files that cannot be loaded are skipped with a warning.
+pub(crate) fn available_themes() -> &'static [Theme] {
+    static THEMES: OnceLock<Vec<Theme>> = OnceLock::new();
+    THEMES.get_or_init(|| {
+        let mut themes: Vec<Theme> = BUILTIN_THEMES.iter()
+            .map(|source| parse_theme(source, "toml", "", Some(&default_theme())).expect("built-in themes are valid"))
+            .collect();
+        for theme in user_themes() {
+            match themes.iter_mut().find(|t| t.name.eq_ignore_ascii_case(&theme.name)) {
+                Some(existing) => *existing = theme,
+                None => themes.push(theme),
+            }
+        }
+        themes
+    })
+}
+
+fn user_themes() -> Vec<Theme> {
+    let Some(dir) = themes_dir().filter(|dir| dir.is_dir()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("toml" | "json")))
+            .collect(),
+        Err(e) => {
+            eprintln!("⚠️  Cannot read themes directory {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| {
+            read_theme_file(path)
+                .map_err(|e| eprintln!("⚠️  Skipping theme: {}", e))
+                .ok()
+        })
+        .collect()
+}
+
+/// `$XDG_CONFIG_HOME/github_skyline/themes` (or `~/.config/...`), and
+/// `%APPDATA%\github_skyline\themes` on Windows.
+pub(crate) fn themes_dir() -> Option<PathBuf> {
+    let config = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    config.map(|dir| dir.join("github_skyline").join("themes"))
+}
+
+fn read_theme_file(path: &Path) -> Result<Theme, Box<dyn std::error::Error>> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read theme file {}: {}", path.display(), e))?;
+    let format = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => "json",
+        _ => "toml",
+    };
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("custom");
+    parse_theme(&source, format, stem, Some(&default_theme())).map_err(|e| format!("Invalid theme file {}: {}", path.display(), e).into())
+}
+
+/// Parses a theme, filling missing fields from `base` (only the default
+/// theme itself is parsed without one, so it must be complete).
+fn parse_theme(source: &str, format: &str, fallback_name: &str, base: Option<&Theme>) -> Result<Theme, String> {
+    let file: ThemeFile = match format {
+        "json" => serde_json::from_str(source).map_err(|e| e.to_string())?,
+        _ => toml::from_str(source).map_err(|e| e.to_string())?,
+    };
+
+    let inherit = |field: &str| -> Result<&Theme, String> {
+        base.ok_or_else(|| format!("missing field '{}'", field))
+    };
+
+    let paint = |spec: Option<String>, field: &str, fallback: fn(&Theme) -> Paint| -> Result<Paint, String> {
+        match spec {
+            Some(spec) => parse_paint(&spec).map_err(|e| format!("{}: {}", field, e)),
+            None => Ok(fallback(inherit(field)?)),
+        }
+    };
+    let ramp = |specs: Option<Vec<String>>, field: &str, fallback: fn(&Theme) -> Vec<Paint>| -> Result<Vec<Paint>, String> {
+        match specs {
+            Some(specs) if specs.is_empty() => Err(format!("{}: needs at least one color", field)),
+            Some(specs) => specs.iter()
+                .map(|spec| parse_paint(spec).map_err(|e| format!("{}: {}", field, e)))
+                .collect(),
+            None => Ok(fallback(inherit(field)?)),
+        }
+    };
+
+    Ok(Theme {
+        name: file.name.unwrap_or_else(|| fallback_name.to_string()),
+        emoji: file.emoji.unwrap_or_else(|| "🎨".to_string()),
+        description: file.description.unwrap_or_else(|| "Custom theme".to_string()),
+        building_colors: ramp(file.buildings, "buildings", |t| t.building_colors.clone())?,
+        window_colors: ramp(file.windows, "windows", |t| t.window_colors.clone())?,
+        antenna_color: paint(file.antenna, "antenna", |t| t.antenna_color)?,
+        roof_color: paint(file.roof, "roof", |t| t.roof_color)?,
+        base_color: paint(file.base, "base", |t| t.base_color)?,
+        sky_color: paint(file.sky, "sky", |t| t.sky_color)?,
+        star_colors: ramp(file.stars, "stars", |t| t.star_colors.clone())?,
+        moon_color: paint(file.moon, "moon", |t| t.moon_color)?,
+        water_colors: ramp(file.water, "water", |t| t.water_colors.clone())?,
+        ground: match file.ground.map(|g| g.to_lowercase()) {
+            Some(ground) if GROUND_MODES.contains(&ground.as_str()) => ground,
+            Some(ground) => return Err(format!("ground: unknown ground '{}', use {}", ground, GROUND_MODES.join(", "))),
+            None => inherit("ground")?.ground.clone(),
+        },
+    })
+}
+
+fn default_theme() -> Theme {
+    static DEFAULT: OnceLock<Theme> = OnceLock::new();
+    DEFAULT.get_or_init(|| {
+        parse_theme(BUILTIN_THEMES[0], "toml", "synthwave", None).expect("synthwave theme is complete")
+    }).clone()
+}
+
+/// Parses `"[bold] <color>"`, where color is a terminal color name such as
+/// `bright_magenta`, a hex code like `#ff2bd6` / `#f2d`, or `rgb(255, 43, 214)`.
+fn parse_paint(spec: &str) -> Result<Paint, String> {
+    let words: Vec<&str> = spec.split_whitespace().collect();
+    let bold = words.iter().any(|w| w.eq_ignore_ascii_case("bold"));
+    let color: Vec<&str> = words.into_iter().filter(|w| !w.eq_ignore_ascii_case("bold")).collect();
+    let color = color.join(" ");
+    if color.is_empty() {
+        return Err(format!("no color in '{}'", spec));
+    }
+    Ok(Paint { color: parse_color(&color)?, bold })
+}
+
+fn parse_color(spec: &str) -> Result<Color, String> {
+    let unknown = || format!("unknown color '{}'", spec);
+    let truecolor = |[r, g, b]: [u8; 3]| Color::TrueColor { r, g, b };
+
+    if let Some(hex) = spec.strip_prefix('#') {
+        let digits: Vec<u8> = hex.chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(unknown)?;
+        return match digits[..] {
+            [r, g, b] => Ok(truecolor([r * 17, g * 17, b * 17])),
+            [r1, r2, g1, g2, b1, b2] => Ok(truecolor([r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2])),
+            _ => Err(unknown()),
+        };
+    }
+    if let Some(channels) = spec.strip_prefix("rgb(").and_then(|rest| rest.strip_suffix(')')) {
+        let values: Vec<u8> = channels.split(',')
+            .map(|v| v.trim().parse::<u8>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| unknown())?;
+        return match values[..] {
+            [r, g, b] => Ok(truecolor([r, g, b])),
+            _ => Err(unknown()),
+        };
+    }
+    spec.replace(['_', '-'], " ").parse::<Color>().map_err(|_| unknown())
+}
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::fs::File;
+use colored::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::renderer::font::glyph_rows;
+use crate::renderer::sky_elements::{moon_for, MoonType};
+use crate::renderer::skyline::RenderOptions;
+use crate::renderer::coloring::BuildingColors;
+use crate::renderer::theme::color_to_rgb;
+
+const DAY_WIDTH: usize = 2;
+const SKY_HEIGHT: usize = 90;
+const CITY_HEIGHT: u32 = 140;
+const WATER_HEIGHT: usize = 14;
+const MOON_RADIUS: i32 = 12;
+// Banding the gradient keeps every frame within GIF's 256-color palette.
+const SKY_BANDS: usize = 48;
+const LABEL_SCALE: usize = 2;
+
+const DUSK_TOP: [u8; 3] = [72, 38, 112];
+const DUSK_HORIZON: [u8; 3] = [250, 138, 72];
+const NIGHT_TOP: [u8; 3] = [4, 6, 24];
+const NIGHT_HORIZON: [u8; 3] = [22, 28, 66];
+const MOON_LIGHT: [u8; 3] = [250, 244, 200];
+const MOON_SHADOW: [u8; 3] = [58, 60, 84];
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas { width, height, pixels: vec![0; width * height * 3] }
+    }
+
+    /// Encodes the canvas with an exact palette, only falling back to
+    /// quantization when the frame uses more than 256 colors.
+    fn to_frame(&self) -> gif::Frame<'static> {
+        let mut palette: Vec<u8> = Vec::new();
+        let mut lookup: HashMap<[u8; 3], u8> = HashMap::new();
+        let mut indices = Vec::with_capacity(self.width * self.height);
+        for rgb in self.pixels.chunks_exact(3) {
+            let key = [rgb[0], rgb[1], rgb[2]];
+            let index = match lookup.get(&key) {
+                Some(&index) => index,
+                None if lookup.len() < 256 => {
+                    let index = lookup.len() as u8;
+                    lookup.insert(key, index);
+                    palette.extend_from_slice(&key);
+                    index
+                }
+                None => return gif::Frame::from_rgb_speed(self.width as u16, self.height as u16, &self.pixels, 10),
+            };
+            indices.push(index);
+        }
+        gif::Frame::from_palette_pixels(self.width as u16, self.height as u16, indices, palette, None)
+    }
+
+    fn set(&mut self, x: usize, y: usize, rgb: [u8; 3]) {
+        if x < self.width && y < self.height {
+            let i = (y * self.width + x) * 3;
+            self.pixels[i..i + 3].copy_from_slice(&rgb);
+        }
+    }
+
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, rgb: [u8; 3]) {
+        for yy in y..y + h {
+            for xx in x..x + w {
+                self.set(xx, yy, rgb);
+            }
+        }
+    }
+}
+
+struct Star {
+    x: usize,
+    y: usize,
+    /// Sky darkness (0-1) at which the star becomes visible.
+    appears_at: f32,
+}
+
+/// Writes an animated GIF where every frame adds the next day (or week) of
+/// contributions to the city. The sky darkens from dusk to night as the
+/// running total grows, and the moon changes shape whenever the total
+/// crosses one of the `select_moon_type` thresholds (or, with `--moon
+/// astronomical`, follows the real phase of each frame's last day).
+pub fn save_timelapse_gif(
+    contributions: &[u32],
+    dates: &[String],
+    options: &RenderOptions,
+    filename: &str,
+    step: &str,
+    fps: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if contributions.is_empty() {
+        return Err("No contribution data to render".into());
+    }
+
+    let days = options.shown_days(contributions.len());
+    if days == 0 {
+        return Err("The time-lapse needs at least one day (--width 1 or more)".into());
+    }
+    let start = contributions.len() - days;
+    let visible = &contributions[start..];
+    let visible_dates = &dates[start.min(dates.len())..];
+
+    let scale = options.height_scale(visible, CITY_HEIGHT);
+    let heights: Vec<u32> = visible.iter().map(|&count| scale.height(count)).collect();
+    let total: u32 = visible.iter().sum();
+
+    let width = days * DAY_WIDTH;
+    let height = SKY_HEIGHT + CITY_HEIGHT as usize + WATER_HEIGHT;
+    let stars = scatter_stars(width, SKY_HEIGHT + CITY_HEIGHT as usize / 2, options.seed);
+    let palette = ThemePalette::new(options, visible);
+
+    let step_days = match step.to_lowercase().as_str() {
+        "day" => 1,
+        "week" => 7,
+        other => return Err(format!("Unknown frame step '{}'. Use day or week.", other).into()),
+    };
+    let mut frame_ends: Vec<usize> = (step_days..days).step_by(step_days).collect();
+    frame_ends.push(days);
+
+    let mut encoder = gif::Encoder::new(File::create(filename)?, width as u16, height as u16, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    let delay = (100 / fps.clamp(1, 50)) as u16;
+
+    for (index, &revealed) in frame_ends.iter().enumerate() {
+        let running_total: u32 = visible[..revealed].iter().sum();
+        let darkness = if total > 0 {
+            running_total as f32 / total as f32
+        } else {
+            revealed as f32 / days as f32
+        };
+
+        let mut canvas = Canvas::new(width, height);
+        paint_sky(&mut canvas, darkness, &stars);
+        paint_moon(&mut canvas, &moon_for(&options.moon, running_total, &visible_dates[..revealed.min(visible_dates.len())]), darkness);
+        paint_city(&mut canvas, &heights[..revealed], &visible[..revealed], visible_dates, &palette);
+        paint_water(&mut canvas, &palette);
+
+        let label = match visible_dates.get(revealed - 1) {
+            Some(date) => format!("{} {}", date, running_total),
+            None => running_total.to_string(),
+        };
+        paint_label(&mut canvas, &label, 4, 4);
+
+        let mut frame = canvas.to_frame();
+        frame.delay = if index + 1 == frame_ends.len() { 300 } else { delay.max(2) };
+        encoder.write_frame(&frame)?;
+    }
+    drop(encoder);
+
+    println!("{}", format!("✅ Time-lapse saved to: {}", filename).bright_green().bold());
+    println!("{}", format!("🎞️  Frames: {}    📁 File size: {} bytes", frame_ends.len(), std::fs::metadata(filename)?.len()).bright_blue());
+
+    Ok(())
+}
+
+struct ThemePalette {
+    colors: BuildingColors,
+    roof: [u8; 3],
+    base: [u8; 3],
+    water: [u8; 3],
+}
+
+impl ThemePalette {
+    fn new(options: &RenderOptions, contributions: &[u32]) -> Self {
+        let colors = BuildingColors::new(&options.theme, &options.color_by, contributions, CITY_HEIGHT);
+        let theme = &colors.theme;
+        ThemePalette {
+            roof: color_to_rgb(theme.roof_color.color),
+            base: color_to_rgb(theme.base_color.color),
+            water: color_to_rgb(theme.water_colors[0].color),
+            colors,
+        }
+    }
+}
+
+fn lerp(from: [u8; 3], to: [u8; 3], t: f32) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0);
+    [0, 1, 2].map(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8)
+}
+
+fn scatter_stars(width: usize, height: usize, seed: u64) -> Vec<Star> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    (0..(width * height) / 180)
+        .map(|_| Star {
+            x: rng.gen_range(0..width),
+            y: rng.gen_range(0..height),
+            appears_at: rng.gen_range(0.25..1.0),
+        })
+        .collect()
+}
+
+fn paint_sky(canvas: &mut Canvas, darkness: f32, stars: &[Star]) {
+    let top = lerp(DUSK_TOP, NIGHT_TOP, darkness);
+    let horizon = lerp(DUSK_HORIZON, NIGHT_HORIZON, darkness);
+    let sky_rows = SKY_HEIGHT + CITY_HEIGHT as usize;
+    let band_color = |y: usize| {
+        let band = y * SKY_BANDS / sky_rows;
+        lerp(top, horizon, band as f32 / SKY_BANDS as f32)
+    };
+    for y in 0..sky_rows {
+        canvas.fill_rect(0, y, canvas.width, 1, band_color(y));
+    }
+    for star in stars.iter().filter(|s| darkness >= s.appears_at) {
+        // Freshly appeared stars are drawn half-bright before reaching full glow.
+        let glow = if darkness - star.appears_at < 0.1 { 0.5 } else { 1.0 };
+        canvas.set(star.x, star.y, lerp(band_color(star.y), [255, 255, 255], glow));
+    }
+}
+
+fn paint_moon(canvas: &mut Canvas, moon_type: &MoonType, darkness: f32) {
+    let (lit_fraction, lit_on_right) = moon_type.illumination();
+    let cx = canvas.width as i32 - MOON_RADIUS * 3;
+    let cy = MOON_RADIUS * 2 + 6;
+    // The terminator is an ellipse: points on the lit side of it are lit.
+    let terminator = 1.0 - 2.0 * lit_fraction;
+    let light = lerp(DUSK_HORIZON, MOON_LIGHT, 0.4 + darkness * 0.6);
+    for dy in -MOON_RADIUS..=MOON_RADIUS {
+        let half_chord = ((MOON_RADIUS * MOON_RADIUS - dy * dy) as f32).sqrt();
+        for dx in -MOON_RADIUS..=MOON_RADIUS {
+            if (dx as f32).abs() > half_chord || cx + dx < 0 {
+                continue;
+            }
+            let side = if lit_on_right { dx } else { -dx };
+            let lit = lit_fraction > 0.0 && side as f32 >= terminator * half_chord;
+            canvas.set((cx + dx) as usize, (cy + dy) as usize, if lit { light } else { MOON_SHADOW });
+        }
+    }
+}
+
+fn paint_city(canvas: &mut Canvas, heights: &[u32], contributions: &[u32], dates: &[String], palette: &ThemePalette) {
+    let ground = SKY_HEIGHT + CITY_HEIGHT as usize;
+    for (i, (&height, &count)) in heights.iter().zip(contributions).enumerate() {
+        if height == 0 {
+            continue;
+        }
+        let date = dates.get(i).map(String::as_str);
+        let h = height as usize;
+        let x = i * DAY_WIDTH;
+        let top = ground - h;
+        canvas.fill_rect(x, top, DAY_WIDTH, h, color_to_rgb(palette.colors.building(height, count, date).color));
+        canvas.fill_rect(x, top, DAY_WIDTH, 1, palette.roof);
+        canvas.fill_rect(x, ground - 1, DAY_WIDTH, 1, palette.base);
+        if count > 0 {
+            let window = color_to_rgb(palette.colors.window(height, count, date).color);
+            for y in (top + 3..ground - 2).step_by(4) {
+                if !(y / 4 + i + count as usize).is_multiple_of(3) {
+                    canvas.set(x + (i % DAY_WIDTH), y, window);
+                }
+            }
+        }
+    }
+}
+
+fn paint_water(canvas: &mut Canvas, palette: &ThemePalette) {
+    let top = SKY_HEIGHT + CITY_HEIGHT as usize;
+    let deep = lerp(palette.water, [10, 20, 60], 0.7);
+    for y in top..canvas.height {
+        for x in 0..canvas.width {
+            let ripple = (x + y * 3) % 11 == 0;
+            canvas.set(x, y, if ripple { lerp(deep, [200, 220, 255], 0.35) } else { deep });
+        }
+    }
+}
+
+fn paint_label(canvas: &mut Canvas, text: &str, x: usize, y: usize) {
+    for (i, ch) in text.chars().enumerate() {
+        for (row, bits) in glyph_rows(ch).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    let px = x + (i * 4 + col) * LABEL_SCALE;
+                    let py = y + row * LABEL_SCALE;
+                    canvas.fill_rect(px, py, LABEL_SCALE, LABEL_SCALE, [235, 235, 245]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::skyline::DEFAULT_WIDTH;
+
+    /// Renders a GIF to a temporary file and returns its frame count and size.
+    fn render(days: usize, options: &RenderOptions, step: &str) -> (usize, u16, u16) {
+        let contributions: Vec<u32> = (0..days as u32).map(|day| day % 5).collect();
+        let dates: Vec<String> = (0..days).map(|day| format!("2024-01-{:02}", day % 31 + 1)).collect();
+        let path = std::env::temp_dir().join(format!("skyline-timelapse-{}-{}-{}.gif", std::process::id(), days, step));
+        let filename = path.to_str().unwrap();
+        save_timelapse_gif(&contributions, &dates, options, filename, step, 12).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new().read_info(File::open(&path).unwrap()).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        std::fs::remove_file(&path).unwrap();
+        (frames, width, height)
+    }
+
+    #[test]
+    fn one_frame_per_step() {
+        let options = RenderOptions { width: Some(10), ..RenderOptions::default() };
+        let height = (SKY_HEIGHT + CITY_HEIGHT as usize + WATER_HEIGHT) as u16;
+        assert_eq!(render(10, &options, "day"), (10, 20, height));
+        assert_eq!(render(10, &options, "week"), (2, 20, height));
+    }
+
+    #[test]
+    fn shows_the_default_width_like_the_terminal() {
+        let (frames, width, _) = render(40, &RenderOptions::default(), "day");
+        assert_eq!(frames, DEFAULT_WIDTH);
+        assert_eq!(width as usize, DEFAULT_WIDTH * DAY_WIDTH);
+    }
+}
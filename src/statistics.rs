@@ -0,0 +1,325 @@
+use serde::Serialize;
+use crate::calendar::{month_abbrev, month_of, today, weekday, year_of};
+
+/// A stretch of consecutive days, with the dates it starts and ends on when
+/// they are known.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Run {
+    pub days: u32,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+impl Run {
+    fn new(days: u32, last_day: usize, dates: &[String]) -> Self {
+        if days == 0 {
+            return Run::default();
+        }
+        let first_day = last_day + 1 - days as usize;
+        Run {
+            days,
+            start: dates.get(first_day).cloned(),
+            end: dates.get(last_day).cloned(),
+        }
+    }
+
+    /// `2024-03-01 → 2024-03-09`, or nothing without dates.
+    pub fn range(&self) -> Option<String> {
+        Some(format!("{} → {}", self.start.as_ref()?, self.end.as_ref()?))
+    }
+}
+
+/// Totals and streaks for a daily contribution series.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Statistics {
+    pub days: usize,
+    pub total: u32,
+    pub average: f32,
+    pub max_day: u32,
+    pub active_days: usize,
+    pub longest_streak: Run,
+    /// Active days up to the last day, or up to the day before when the
+    /// last day has nothing yet.
+    pub current_streak: Run,
+    /// The current streak ends yesterday and today has no contributions yet.
+    pub streak_at_risk: bool,
+    /// Longest run of days without contributions.
+    pub longest_gap: Run,
+}
+
+impl Statistics {
+    pub fn new(contributions: &[u32], dates: &[String]) -> Self {
+        let total: u32 = contributions.iter().sum();
+        let current_streak = current_streak(contributions, dates);
+        let last_day_empty = contributions.last() == Some(&0);
+        Statistics {
+            days: contributions.len(),
+            total,
+            average: total as f32 / contributions.len().max(1) as f32,
+            max_day: contributions.iter().copied().max().unwrap_or(0),
+            active_days: contributions.iter().filter(|&&x| x > 0).count(),
+            longest_streak: longest_run(contributions, dates, |count| count > 0),
+            streak_at_risk: current_streak.days > 0 && last_day_empty && dates.last() == Some(&today()),
+            current_streak,
+            longest_gap: longest_run(contributions, dates, |count| count == 0),
+        }
+    }
+
+    pub fn active_percent(&self) -> f32 {
+        self.active_days as f32 / self.days.max(1) as f32 * 100.0
+    }
+}
+
+/// Length of the longest run of active days.
+pub fn calculate_longest_streak(contributions: &[u32]) -> u32 {
+    longest_run(contributions, &[], |count| count > 0).days
+}
+
+/// The longest run of days matching `keep`; the most recent one wins ties.
+fn longest_run(contributions: &[u32], dates: &[String], keep: impl Fn(u32) -> bool) -> Run {
+    let mut best = (0, 0);
+    let mut current = 0;
+    for (day, &count) in contributions.iter().enumerate() {
+        current = if keep(count) { current + 1 } else { 0 };
+        if current > 0 && current >= best.0 {
+            best = (current, day);
+        }
+    }
+    Run::new(best.0, best.1, dates)
+}
+
+fn current_streak(contributions: &[u32], dates: &[String]) -> Run {
+    let last_day = match contributions.split_last() {
+        Some((0, earlier)) if !earlier.is_empty() => earlier.len() - 1,
+        Some((0, _)) | None => return Run::default(),
+        _ => contributions.len() - 1,
+    };
+    let days = contributions[..=last_day].iter().rev().take_while(|&&count| count > 0).count() as u32;
+    Run::new(days, last_day, dates)
+}
+
+/// How contributions spread over the week and the year, and where they are
+/// heading lately.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Insights {
+    /// Contributions on each weekday, Sunday first.
+    pub weekdays: [u32; 7],
+    /// Contributions per calendar month, oldest first.
+    pub months: Vec<MonthTotal>,
+    pub busiest_month: Option<MonthTotal>,
+    /// Median and 90th percentile of the active days' counts.
+    pub median: u32,
+    pub p90: u32,
+    /// Averages over the last 7 and 30 days.
+    pub rolling_7: f32,
+    pub rolling_30: f32,
+    pub week_over_week: Trend,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MonthTotal {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub total: u32,
+}
+
+impl MonthTotal {
+    /// `Mar 2025`.
+    pub fn label(&self) -> String {
+        let date = format!("{}-01", self.month);
+        match (month_of(&date), year_of(&date)) {
+            (Some(month), Some(year)) => format!("{} {}", month_abbrev(month), year),
+            _ => self.month.clone(),
+        }
+    }
+}
+
+/// The last 7 days against the 7 before them.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Trend {
+    pub this_week: u32,
+    pub last_week: u32,
+    /// `None` when last week had nothing to compare against.
+    pub change_percent: Option<f32>,
+}
+
+impl Insights {
+    pub fn new(contributions: &[u32], dates: &[String]) -> Self {
+        let mut weekdays = [0; 7];
+        let mut months: Vec<MonthTotal> = Vec::new();
+        for (date, &count) in dates.iter().zip(contributions) {
+            if let Some(day) = weekday(date) {
+                weekdays[day] += count;
+            }
+            let month = date.get(..7).unwrap_or(date);
+            match months.last_mut() {
+                Some(last) if last.month == month => last.total += count,
+                _ => months.push(MonthTotal { month: month.to_string(), total: count }),
+            }
+        }
+        // The first month wins ties, like the lighthouse on the busiest day.
+        let busiest_month = months
+            .iter()
+            .filter(|month| month.total > 0)
+            .fold(None::<&MonthTotal>, |best, month| match best {
+                Some(best) if best.total >= month.total => Some(best),
+                _ => Some(month),
+            })
+            .cloned();
+
+        let mut active: Vec<u32> = contributions.iter().copied().filter(|&count| count > 0).collect();
+        active.sort_unstable();
+        let week = |weeks_ago: usize| -> u32 {
+            let end = contributions.len().saturating_sub(weeks_ago * 7);
+            contributions[end.saturating_sub(7)..end].iter().sum()
+        };
+        let (this_week, last_week) = (week(0), week(1));
+
+        Insights {
+            weekdays,
+            months,
+            busiest_month,
+            median: percentile(&active, 50.0),
+            p90: percentile(&active, 90.0),
+            rolling_7: rolling_average(contributions, 7).last().copied().unwrap_or(0.0),
+            rolling_30: rolling_average(contributions, 30).last().copied().unwrap_or(0.0),
+            week_over_week: Trend {
+                this_week,
+                last_week,
+                change_percent: (last_week > 0).then(|| (this_week as f32 - last_week as f32) / last_week as f32 * 100.0),
+            },
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted counts, 0 when there are none.
+pub fn percentile(sorted: &[u32], p: f32) -> u32 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f32).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// The average of each day and up to `window - 1` days before it.
+pub fn rolling_average(contributions: &[u32], window: usize) -> Vec<f32> {
+    let mut sum = 0;
+    contributions
+        .iter()
+        .enumerate()
+        .map(|(day, &count)| {
+            sum += count;
+            if day >= window {
+                sum -= contributions[day - window];
+            }
+            sum as f32 / (day + 1).min(window) as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{civil_from_days, days_from_civil, parse_date};
+
+    /// `days` consecutive dates ending on `last`.
+    fn dates_ending(last: &str, days: usize) -> Vec<String> {
+        let (year, month, day) = parse_date(last).unwrap();
+        let end = days_from_civil(year, month, day);
+        (0..days as i64)
+            .map(|i| {
+                let (y, m, d) = civil_from_days(end - (days as i64 - 1) + i);
+                format!("{:04}-{:02}-{:02}", y, m, d)
+            })
+            .collect()
+    }
+
+    fn run(days: u32, start: &str, end: &str) -> Run {
+        Run { days, start: Some(start.to_string()), end: Some(end.to_string()) }
+    }
+
+    #[test]
+    fn empty_input() {
+        let stats = Statistics::new(&[], &[]);
+        assert_eq!(stats.days, 0);
+        assert_eq!(stats.total, 0);
+        assert_eq!(stats.average, 0.0);
+        assert_eq!(stats.max_day, 0);
+        assert_eq!(stats.active_percent(), 0.0);
+        assert_eq!(stats.longest_streak, Run::default());
+        assert_eq!(stats.current_streak, Run::default());
+        assert_eq!(stats.longest_gap, Run::default());
+        assert!(!stats.streak_at_risk);
+    }
+
+    #[test]
+    fn all_zeros() {
+        let dates = dates_ending("2024-03-05", 5);
+        let stats = Statistics::new(&[0; 5], &dates);
+        assert_eq!(stats.active_days, 0);
+        assert_eq!(stats.longest_streak, Run::default());
+        assert_eq!(stats.current_streak, Run::default());
+        assert_eq!(stats.longest_gap, run(5, "2024-03-01", "2024-03-05"));
+        assert!(!stats.streak_at_risk);
+    }
+
+    #[test]
+    fn current_streak_runs_to_the_last_day() {
+        let dates = dates_ending("2024-03-05", 5);
+        let stats = Statistics::new(&[0, 2, 0, 1, 3], &dates);
+        assert_eq!(stats.current_streak, run(2, "2024-03-04", "2024-03-05"));
+        assert!(!stats.streak_at_risk);
+    }
+
+    #[test]
+    fn empty_last_day_keeps_yesterdays_streak() {
+        let dates = dates_ending("2024-03-05", 5);
+        let stats = Statistics::new(&[0, 2, 1, 4, 0], &dates);
+        assert_eq!(stats.current_streak, run(3, "2024-03-02", "2024-03-04"));
+        // Only today can put a streak at risk.
+        assert!(!stats.streak_at_risk);
+
+        let stats = Statistics::new(&[0, 2, 1, 4, 0], &dates_ending(&today(), 5));
+        assert_eq!(stats.current_streak.days, 3);
+        assert!(stats.streak_at_risk);
+    }
+
+    #[test]
+    fn two_empty_days_end_the_streak() {
+        let stats = Statistics::new(&[3, 3, 0, 0], &dates_ending(&today(), 4));
+        assert_eq!(stats.current_streak, Run::default());
+        assert!(!stats.streak_at_risk);
+    }
+
+    #[test]
+    fn single_day() {
+        assert_eq!(Statistics::new(&[0], &[]).current_streak, Run::default());
+        let stats = Statistics::new(&[4], &[]);
+        assert_eq!(stats.current_streak, Run { days: 1, start: None, end: None });
+        assert_eq!(stats.longest_streak.days, 1);
+        assert_eq!(stats.longest_gap, Run::default());
+    }
+
+    #[test]
+    fn ties_go_to_the_most_recent_run() {
+        let dates = dates_ending("2024-03-07", 7);
+        let stats = Statistics::new(&[1, 1, 0, 0, 1, 1, 0], &dates);
+        assert_eq!(stats.longest_streak, run(2, "2024-03-05", "2024-03-06"));
+        assert_eq!(stats.longest_gap, run(2, "2024-03-03", "2024-03-04"));
+    }
+
+    #[test]
+    fn runs_without_dates() {
+        let stats = Statistics::new(&[1, 1, 1, 0, 1], &[]);
+        assert_eq!(stats.longest_streak, Run { days: 3, start: None, end: None });
+        assert_eq!(stats.longest_streak.range(), None);
+        assert_eq!(run(2, "2024-03-05", "2024-03-06").range().as_deref(), Some("2024-03-05 → 2024-03-06"));
+    }
+
+    #[test]
+    fn longest_streak_count() {
+        assert_eq!(calculate_longest_streak(&[]), 0);
+        assert_eq!(calculate_longest_streak(&[0, 0]), 0);
+        assert_eq!(calculate_longest_streak(&[1, 0, 1, 1, 1, 0, 2, 2]), 3);
+    }
+}
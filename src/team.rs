@@ -0,0 +1,220 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use colored::*;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::achievements::{calculate_achievements, display_achievements};
+use crate::api::client::GitHubClient;
+use crate::renderer::ground::ground_lines;
+use crate::renderer::sky_elements::{moon_for, print_sky, SkyLayout};
+use crate::renderer::skyline::{print_insights, print_statistics, style_glyphs, RenderOptions};
+use crate::renderer::theme::{get_theme, Paint, Theme};
+
+/// Calendars fetched at the same time unless `--jobs` says otherwise.
+pub const DEFAULT_JOBS: usize = 4;
+
+/// Several users' calendars lined up on the same days.
+pub struct Team {
+    pub name: String,
+    pub dates: Vec<String>,
+    /// Each member's login and daily contributions, in the order given.
+    pub members: Vec<(String, Vec<u32>)>,
+}
+
+impl Team {
+    /// The team's combined contributions for each day.
+    pub fn totals(&self) -> Vec<u32> {
+        (0..self.dates.len())
+            .map(|day| self.members.iter().map(|(_, contributions)| contributions[day]).sum())
+            .collect()
+    }
+}
+
+/// Reads usernames separated by newlines, commas or spaces; `#` starts a
+/// comment.
+pub fn read_users_file(path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read users file {}: {}", path, e))?;
+    Ok(text
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|login| !login.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Fetches every member's calendar, at most `jobs` at a time. Members that
+/// cannot be fetched are reported and left out of the team.
+pub async fn fetch_team(
+    client: &GitHubClient,
+    name: &str,
+    logins: Vec<String>,
+    year: Option<i32>,
+    jobs: usize,
+) -> Result<Team, Box<dyn std::error::Error>> {
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+    let mut tasks = JoinSet::new();
+    for (index, login) in logins.into_iter().enumerate() {
+        let client = client.clone();
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let calendar = client.fetch_contributions_in(&login, year).await.map_err(|e| e.to_string());
+            (index, login, calendar)
+        });
+    }
+
+    let mut fetched = Vec::new();
+    while let Some(task) = tasks.join_next().await {
+        let (index, login, calendar) = task?;
+        match calendar {
+            Ok(calendar) => {
+                eprintln!("✅ {}: {} contributions", login, calendar.total_contributions);
+                fetched.push((index, login, calendar.flatten_dates(), calendar.flatten_contributions()));
+            }
+            Err(e) => eprintln!("⚠️  Skipping {}: {}", login, e),
+        }
+    }
+    if fetched.is_empty() {
+        return Err("No team member's contributions could be fetched".into());
+    }
+    fetched.sort_by_key(|(index, ..)| *index);
+
+    // Members normally share the same days, but line them up by date anyway.
+    let dates: Vec<String> = fetched
+        .iter()
+        .flat_map(|(_, _, dates, _)| dates.iter().cloned())
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .collect();
+    let day_of: HashMap<&str, usize> = dates.iter().enumerate().map(|(day, date)| (date.as_str(), day)).collect();
+    let members = fetched
+        .iter()
+        .map(|(_, login, member_dates, contributions)| {
+            let mut aligned = vec![0; dates.len()];
+            for (date, &count) in member_dates.iter().zip(contributions) {
+                aligned[day_of[date.as_str()]] += count;
+            }
+            (login.clone(), aligned)
+        })
+        .collect();
+    Ok(Team { name: name.to_string(), dates, members })
+}
+
+/// The team city with each building split into one band per member, in
+/// member order from the ground up, sized by their share of the day.
+pub fn render_team_skyline(team: &Team, options: &RenderOptions) {
+    let totals = team.totals();
+    if totals.is_empty() {
+        println!("{}", "❌ No contribution data to render!".bright_red().bold());
+        return;
+    }
+    let theme = get_theme(&options.theme);
+    let width = options.shown_days(totals.len());
+
+    println!("\n{}", "╔═══════════════════════════════════════════════════════════════╗".bright_cyan().bold());
+    println!("{}", "║                      👥 TEAM SKYLINE 👥                       ║".bright_cyan().bold());
+    println!("{}", "╚═══════════════════════════════════════════════════════════════╝".bright_cyan().bold());
+    println!("{}", format!("🏢 {} · {} members", team.name, team.members.len()).bright_yellow().bold());
+    if let Some(reference) = &options.reference {
+        println!("{}", format!("📏 Shared scale: {} (busiest day {})", reference.label, reference.max()).bright_yellow().bold());
+    }
+
+    let layout = SkyLayout::new(&totals, &team.dates, width, options.cell, 0, options.ascii_only);
+    let moon_type = moon_for(&options.moon, totals.iter().sum(), &team.dates);
+    print_sky(&options.sky_mode, &layout, &moon_type, &options.theme, options.seed);
+    let lines = stacked_lines(team, &totals, &theme, options, width);
+    let ground = ground_lines(&options.ground(), &lines, options.cell.columns(width), &theme, options.ascii_only, options.seed);
+    for line in lines.iter().chain(&ground) {
+        println!("{}", line);
+    }
+
+    let glyphs = style_glyphs(&options.style.to_lowercase(), options.ascii_only);
+    let team_total: u32 = totals.iter().sum();
+    println!("\n{}", "👥 MEMBERS".bright_green().bold());
+    for (index, (login, contributions)) in team.members.iter().enumerate() {
+        let total: u32 = contributions.iter().sum();
+        let glyph = if index % 2 == 1 { glyphs.window } else { glyphs.body };
+        println!(
+            "   {}  {} {}",
+            member_paint(&theme, index, team.members.len()).paint(glyph),
+            format!("{:<20}", login).bright_white(),
+            format!("{:>6} ({:.1}%)", total, total as f32 / team_total.max(1) as f32 * 100.0).bright_yellow()
+        );
+    }
+
+    print_statistics(&totals, &team.dates, width);
+    print_insights(&totals, &team.dates, options.ascii_only);
+    display_achievements(&calculate_achievements(&totals));
+}
+
+fn stacked_lines(team: &Team, totals: &[u32], theme: &Theme, options: &RenderOptions, width: usize) -> Vec<String> {
+    let scale = options.height_scale(totals, options.height);
+    let glyphs = style_glyphs(&options.style.to_lowercase(), options.ascii_only);
+    let cell = options.cell;
+    let offset = totals.len() - width;
+    // Each shown day's bands, bottom up.
+    let days: Vec<(u32, Vec<u32>)> = (offset..totals.len())
+        .map(|day| {
+            let height = scale.height(totals[day]);
+            let counts: Vec<u32> = team.members.iter().map(|(_, contributions)| contributions[day]).collect();
+            (height, split_rows(height, &counts))
+        })
+        .collect();
+    let max_height = days.iter().map(|(height, _)| *height).max().unwrap_or(0) + 1;
+
+    (1..=max_height)
+        .rev()
+        .map(|row| {
+            days.iter()
+                .map(|(height, bands)| {
+                    if row > *height {
+                        return cell.blank();
+                    }
+                    let mut top = 0;
+                    let member = bands.iter().position(|&rows| {
+                        top += rows;
+                        row <= top
+                    });
+                    let member = member.unwrap_or(0);
+                    // Alternate glyphs so neighbouring bands stay apart without color.
+                    let glyph = match row {
+                        _ if row == *height => glyphs.roof,
+                        _ if member % 2 == 1 => glyphs.window,
+                        _ => glyphs.body,
+                    };
+                    member_paint(theme, member, team.members.len()).paint(&cell.stretch(glyph)).to_string()
+                })
+                .collect::<Vec<String>>()
+                .join(&cell.spacer())
+        })
+        .collect()
+}
+
+/// Shares `height` rows out by `counts`, giving leftover rows to the largest
+/// remainders.
+fn split_rows(height: u32, counts: &[u32]) -> Vec<u32> {
+    let total: u32 = counts.iter().sum();
+    if total == 0 {
+        return vec![0; counts.len()];
+    }
+    let exact: Vec<f32> = counts.iter().map(|&count| count as f32 * height as f32 / total as f32).collect();
+    let mut rows: Vec<u32> = exact.iter().map(|share| share.floor() as u32).collect();
+    let mut order: Vec<usize> = (0..counts.len()).collect();
+    order.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+    let missing = height.saturating_sub(rows.iter().sum());
+    for &member in order.iter().take(missing as usize) {
+        rows[member] += 1;
+    }
+    rows
+}
+
+/// A member's color, spread across the theme's building ramp.
+fn member_paint(theme: &Theme, member: usize, members: usize) -> Paint {
+    let colors = &theme.building_colors;
+    if members <= colors.len() {
+        colors[member * (colors.len() - 1) / (members - 1).max(1)]
+    } else {
+        colors[member % colors.len()]
+    }
+}